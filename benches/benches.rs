@@ -1,6 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-use cqlparser::parse;
+use cqlparser::{parse, parse_with, ParseOptions};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("select");
@@ -27,7 +27,11 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 
     group.bench_function("christmas_tree", |b| {
-        b.iter(|| parse(black_box("SELECT distinct json field1, field2 as foo FROM table order by order_column WHERE foo = 1 DESC limit 9999 allow filtering")))
+        b.iter(|| parse(black_box("SELECT json distinct field1, field2 as foo FROM table WHERE foo = 1 order by order_column DESC limit 9999 allow filtering")))
+    });
+
+    group.bench_function("christmas_tree_strict", |b| {
+        b.iter(|| parse_with(black_box("SELECT json distinct field1, field2 as foo FROM table WHERE foo = 1 order by order_column DESC limit 9999 allow filtering"), ParseOptions { strict: true, ..ParseOptions::default() }))
     });
 }
 
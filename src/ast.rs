@@ -1,67 +1,1112 @@
+//! The syntax tree produced by the parser.
+//!
+//! With the `serde` feature enabled every type here implements `Serialize` and `Deserialize`.
+//! Structs become objects keyed by their field names. Enums are adjacently tagged: an object
+//! whose `type` is the variant name and whose `value` holds the variant's data, omitted for
+//! variants without data. So `SELECT * FROM t WHERE k = ?` serializes to:
+//!
+//! ```json
+//! {"type": "Select", "value": {
+//!     "distinct": false, "json": false,
+//!     "select": [{"expr": {"type": "Wildcard", "value": {"start": 7, "end": 8}}, "as_alias": null,
+//!         "span": {"start": 7, "end": 8}}],
+//!     "from": {"keyspace": null, "name": {"value": "t", "span": {"start": 14, "end": 15}},
+//!         "span": {"start": 14, "end": 15}},
+//!     "where_": [{"type": "Comparison", "value": {
+//!         "lhs": {"type": "Name", "value": {"value": "k", "span": {"start": 22, "end": 23}}},
+//!         "operator": {"type": "Equals"},
+//!         "rhs": {"type": "BindMarker", "value": [{"type": "Anonymous"}, {"start": 26, "end": 27}]},
+//!         "span": {"start": 22, "end": 27}}}],
+//!     "group_by": [], "order_by": [], "per_partition_limit": null, "limit": null,
+//!     "allow_filtering": false, "using": null}}
+//! ```
+//!
+//! Tuple variants with several fields, such as `CqlType::Map` or an `Expr` and its span, hold an
+//! array, and `Constant::Hex` holds an array of byte values. A `Constant::Float` that is not finite
+//! has no JSON representation and serializes as `null`, which does not deserialize.
+
+/// A statement, whose span [`parse_with_spans`](crate::parse_with_spans) returns alongside it
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Statement {
     Select(Select),
     Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+    Batch(Batch),
+    CreateKeyspace(CreateKeyspace),
+    AlterKeyspace(AlterKeyspace),
+    DropKeyspace(DropKeyspace),
+    CreateTable(CreateTable),
+    AlterTable(AlterTable),
+    DropTable(DropTable),
+    Truncate(Truncate),
+    CreateIndex(CreateIndex),
+    DropIndex(DropIndex),
+    CreateMaterializedView(CreateMaterializedView),
+    AlterMaterializedView(AlterMaterializedView),
+    DropMaterializedView(DropMaterializedView),
+    CreateType(CreateType),
+    AlterType(AlterType),
+    DropType(DropType),
+    CreateFunction(CreateFunction),
+    DropFunction(DropFunction),
+    CreateAggregate(CreateAggregate),
+    DropAggregate(DropAggregate),
+    CreateTrigger(CreateTrigger),
+    DropTrigger(DropTrigger),
+    CreateRole(CreateRole),
+    AlterRole(AlterRole),
+    DropRole(DropRole),
+    CreateUser(CreateUser),
+    AlterUser(AlterUser),
+    DropUser(DropUser),
+    GrantRole(GrantRole),
+    RevokeRole(RevokeRole),
+    GrantPermission(GrantPermission),
+    RevokePermission(RevokePermission),
+    ListRoles(ListRoles),
+    ListUsers,
+    ListPermissions(ListPermissions),
+    /// `USE keyspace`
+    Use(Identifier),
+    Describe(Describe),
+}
+
+/// An element of a script parsed by `parse_script`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// Most scripts are mostly statements, so boxing them would cost more than it saves
+#[allow(clippy::large_enum_variant)]
+pub enum ScriptElement {
+    Statement(Statement),
+    /// Only produced when `ParseOptions::cqlsh` is set
+    ShellCommand(ShellCommand),
+}
+
+/// A command interpreted by cqlsh itself rather than sent to Cassandra.
+/// Commands that take an argument show the current setting when it is omitted.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum ShellCommand {
+    /// `CONSISTENCY [level]`
+    Consistency(Option<ConsistencyLevel>),
+    /// `SERIAL CONSISTENCY [level]`
+    SerialConsistency(Option<ConsistencyLevel>),
+    /// `TRACING [ON | OFF]`
+    Tracing(Option<bool>),
+    /// `PAGING [ON | OFF | page_size]`
+    Paging(Option<Paging>),
+    /// `EXPAND [ON | OFF]`
+    Expand(Option<bool>),
+    /// `SOURCE 'file'`
+    Source(String),
+    /// `CAPTURE ['file' | OFF]`
+    Capture(Option<Capture>),
+    Copy(Copy),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum ConsistencyLevel {
+    Any,
+    One,
+    Two,
+    Three,
+    Quorum,
+    All,
+    LocalQuorum,
+    EachQuorum,
+    Serial,
+    LocalSerial,
+    LocalOne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Paging {
+    On,
+    Off,
+    PageSize(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Capture {
+    File(String),
+    Off,
+}
+
+/// `COPY table [(column, ...)] TO|FROM 'file'|STDOUT|STDIN [WITH option = value AND ...]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Copy {
+    pub table: QualifiedName,
+    /// Every column when empty
+    pub columns: Vec<Identifier>,
+    pub direction: CopyDirection,
+    /// `None` for `STDOUT` or `STDIN`
+    pub file: Option<String>,
+    pub options: Vec<Property>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum CopyDirection {
+    /// `TO`, exporting the table
+    To,
+    /// `FROM`, importing into the table
+    From,
+}
+
+impl Statement {
+    /// Returns true if the statement is a lightweight transaction, i.e. it has an `IF` condition
+    /// and so must be executed with paxos at serial consistency.
+    pub fn is_lwt(&self) -> bool {
+        match self {
+            Statement::Insert(insert) => insert.condition.is_some(),
+            Statement::Update(update) => update.condition.is_some(),
+            Statement::Delete(delete) => delete.condition.is_some(),
+            Statement::Batch(batch) => batch.statements.iter().any(|statement| match statement {
+                BatchStatement::Insert(insert) => insert.condition.is_some(),
+                BatchStatement::Update(update) => update.condition.is_some(),
+                BatchStatement::Delete(delete) => delete.condition.is_some(),
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// A range of byte offsets into the parsed input, `start` inclusive and `end` exclusive.
+///
+/// Spans take part in comparisons, so the same statement parsed from differently formatted
+/// input compares unequal. Compare the rendered statements to ignore formatting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The location of `start` in `source`, which must be the input the span was parsed from.
+    pub fn start_location(&self, source: &str) -> Location {
+        line_col(source, self.start)
+    }
+
+    /// The location of `end` in `source`, which must be the input the span was parsed from.
+    pub fn end_location(&self, source: &str) -> Location {
+        line_col(source, self.end)
+    }
+}
+
+/// A line and column, both starting at 1. Columns count characters rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Returns the line and column of the byte `offset` into `source`.
+///
+/// Panics if `offset` is past the end of `source` or not on a character boundary.
+pub fn line_col(source: &str, offset: usize) -> Location {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Location {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+/// A keyspace, table, column or other schema name.
+/// Unquoted identifiers are lowercased when parsed, quoted identifiers keep their case and are stored without quotes.
+///
+/// Derefs to the name, so it can be used wherever a `&str` is expected.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identifier {
+    pub value: String,
+    pub span: Span,
+}
+
+impl Identifier {
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
+
+impl std::ops::Deref for Identifier {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl AsRef<str> for Identifier {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl PartialEq<str> for Identifier {
+    fn eq(&self, other: &str) -> bool {
+        self.value == other
+    }
+}
+
+impl PartialEq<&str> for Identifier {
+    fn eq(&self, other: &&str) -> bool {
+        self.value == *other
+    }
+}
+
+impl From<&str> for Identifier {
+    fn from(value: &str) -> Identifier {
+        Identifier::from(value.to_string())
+    }
+}
+
+impl From<String> for Identifier {
+    fn from(value: String) -> Identifier {
+        Identifier {
+            value,
+            span: Span::default(),
+        }
+    }
+}
+
+impl From<Identifier> for String {
+    fn from(identifier: Identifier) -> String {
+        identifier.value
+    }
+}
+
+/// The name of a table, function or other schema object, optionally qualified with its keyspace, e.g. `ks.table`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QualifiedName {
+    pub keyspace: Option<Identifier>,
+    pub name: Identifier,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Insert {
+    pub table: QualifiedName,
+    pub columns: Vec<Identifier>,
+    pub values: Vec<Expr>,
+    /// Can only be `Condition::NotExists`
+    pub condition: Option<Condition>,
+    pub using: Option<UsingClause>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Update {
+    pub table: QualifiedName,
+    pub using: Option<UsingClause>,
+    pub set: Vec<Assignment>,
+    /// Every element is AND'd together
+    pub where_: Vec<RelationElement>,
+    pub condition: Option<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delete {
+    /// The columns or collection elements to delete, empty when deleting the whole row
+    pub columns: Vec<Expr>,
+    pub table: QualifiedName,
+    /// Can only contain a timestamp and a timeout
+    pub using: Option<UsingClause>,
+    /// Every element is AND'd together
+    pub where_: Vec<RelationElement>,
+    pub condition: Option<Condition>,
+}
+
+/// `BEGIN BATCH ... APPLY BATCH`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Batch {
+    pub batch_type: BatchType,
+    pub using: Option<UsingClause>,
+    pub statements: Vec<BatchStatement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum BatchType {
+    Logged,
+    Unlogged,
+    Counter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum BatchStatement {
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+}
+
+/// `USING TTL n AND TIMESTAMP n AND TIMEOUT d`
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UsingClause {
+    pub ttl: Option<UsingValue>,
+    pub timestamp: Option<UsingValue>,
+    /// ScyllaDB only, accepted on both reads and writes
+    pub timeout: Option<UsingValue>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum UsingValue {
+    Integer(i64),
+    /// A duration literal such as `500ms` or `1h30m`, stored as written
+    Duration(String),
+    BindMarker(BindMarker),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateKeyspace {
+    pub if_not_exists: bool,
+    pub name: Identifier,
+    pub replication: ReplicationStrategy,
+    pub durable_writes: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlterKeyspace {
+    pub if_exists: bool,
+    pub name: Identifier,
+    pub replication: Option<ReplicationStrategy>,
+    pub durable_writes: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropKeyspace {
+    pub if_exists: bool,
+    pub name: Identifier,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateTable {
+    pub if_not_exists: bool,
+    pub name: QualifiedName,
+    pub columns: Vec<ColumnDefinition>,
+    pub partition_key: Vec<Identifier>,
+    pub clustering_key: Vec<Identifier>,
+    /// `WITH CLUSTERING ORDER BY (...)`
+    pub clustering_order: Vec<OrderBy>,
+    /// `WITH COMPACT STORAGE`
+    pub compact_storage: bool,
+    /// Every other `WITH` option
+    pub options: Vec<Property>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlterTable {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+    pub operation: AlterTableOperation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum AlterTableOperation {
+    /// `ADD col type` or `ADD (col type, ...)`
+    Add(Vec<ColumnDefinition>),
+    /// `DROP col` or `DROP (col, ...)`
+    Drop(Vec<Identifier>),
+    /// `RENAME from TO to AND ...`
+    Rename(Vec<(Identifier, Identifier)>),
+    /// `WITH option = value AND ...`
+    With(Vec<Property>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropTable {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Truncate {
+    pub name: QualifiedName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateIndex {
+    /// `CREATE CUSTOM INDEX`
+    pub custom: bool,
+    pub if_not_exists: bool,
+    /// Cassandra generates a name when none is given
+    pub name: Option<Identifier>,
+    pub table: QualifiedName,
+    pub targets: Vec<IndexTarget>,
+    /// The index implementation class given by `USING 'class'`, e.g. `'StorageAttachedIndex'`
+    pub using: Option<String>,
+    /// `WITH OPTIONS = {...}`
+    pub options: Vec<(Constant, Constant)>,
+}
+
+/// The column, or facet of a collection column, that an index is built over
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum IndexTarget {
+    /// `col`, for a collection column this indexes its values
+    Column(Identifier),
+    /// `keys(map_col)`
+    Keys(Identifier),
+    /// `values(col)`
+    Values(Identifier),
+    /// `entries(map_col)`
+    Entries(Identifier),
+    /// `full(frozen_col)`
+    Full(Identifier),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropIndex {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateMaterializedView {
+    pub if_not_exists: bool,
+    pub name: QualifiedName,
+    /// `AS SELECT ... FROM base_table WHERE ...`
+    pub select: Select,
+    pub partition_key: Vec<Identifier>,
+    pub clustering_key: Vec<Identifier>,
+    /// `WITH CLUSTERING ORDER BY (...)`
+    pub clustering_order: Vec<OrderBy>,
+    /// Every other `WITH` option
+    pub options: Vec<Property>,
+}
+
+impl CreateMaterializedView {
+    /// The table the view is built from
+    pub fn base_table(&self) -> &QualifiedName {
+        &self.select.from
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlterMaterializedView {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+    pub options: Vec<Property>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropMaterializedView {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateType {
+    pub if_not_exists: bool,
+    pub name: QualifiedName,
+    pub fields: Vec<FieldDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlterType {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+    pub operation: AlterTypeOperation,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Insert {}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum AlterTypeOperation {
+    /// `ADD field type`
+    Add(FieldDefinition),
+    /// `RENAME from TO to AND ...`
+    Rename(Vec<(Identifier, Identifier)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropType {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateFunction {
+    pub or_replace: bool,
+    pub if_not_exists: bool,
+    pub name: QualifiedName,
+    pub arguments: Vec<FunctionArgument>,
+    /// `CALLED ON NULL INPUT` when true, `RETURNS NULL ON NULL INPUT` when false
+    pub called_on_null_input: bool,
+    pub return_type: CqlType,
+    pub language: String,
+    /// Either a `Constant::CodeBlock` or a `Constant::String`
+    pub body: Constant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionArgument {
+    pub name: Identifier,
+    pub data_type: CqlType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropFunction {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+    /// The argument types selecting a single overload, `None` drops the only overload
+    pub argument_types: Option<Vec<CqlType>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateAggregate {
+    pub or_replace: bool,
+    pub if_not_exists: bool,
+    pub name: QualifiedName,
+    pub argument_types: Vec<CqlType>,
+    /// `SFUNC`, resolved in the aggregate's keyspace
+    pub state_function: Identifier,
+    /// `STYPE`
+    pub state_type: CqlType,
+    /// `FINALFUNC`, resolved in the aggregate's keyspace
+    pub final_function: Option<Identifier>,
+    /// `INITCOND`
+    pub initial_condition: Option<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropAggregate {
+    pub if_exists: bool,
+    pub name: QualifiedName,
+    /// The argument types selecting a single overload, `None` drops the only overload
+    pub argument_types: Option<Vec<CqlType>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateTrigger {
+    pub if_not_exists: bool,
+    pub name: Identifier,
+    pub table: QualifiedName,
+    /// The trigger implementation class given by `USING 'class'`
+    pub using: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropTrigger {
+    pub if_exists: bool,
+    pub name: Identifier,
+    pub table: QualifiedName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateRole {
+    pub if_not_exists: bool,
+    pub name: Identifier,
+    pub options: Vec<RoleOption>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlterRole {
+    pub name: Identifier,
+    pub options: Vec<RoleOption>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropRole {
+    pub if_exists: bool,
+    pub name: Identifier,
+}
+
+/// An option of `CREATE ROLE` or `ALTER ROLE`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum RoleOption {
+    /// `PASSWORD = 'password'`
+    Password(String),
+    /// `HASHED PASSWORD = 'hash'`
+    HashedPassword(String),
+    /// `LOGIN = bool`
+    Login(bool),
+    /// `SUPERUSER = bool`
+    Superuser(bool),
+    /// `OPTIONS = {...}`, passed to the authenticator
+    Options(Vec<(Constant, Constant)>),
+    /// `ACCESS TO DATACENTERS {'dc', ...}`
+    AccessToDatacenters(Vec<String>),
+    /// `ACCESS TO ALL DATACENTERS`
+    AccessToAllDatacenters,
+}
+
+/// The legacy `CREATE USER`, equivalent to `CREATE ROLE` with `LOGIN = true`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateUser {
+    pub if_not_exists: bool,
+    pub name: Identifier,
+    /// `WITH PASSWORD 'password'`
+    pub password: Option<String>,
+    /// `SUPERUSER` or `NOSUPERUSER`
+    pub superuser: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlterUser {
+    pub name: Identifier,
+    /// `WITH PASSWORD 'password'`
+    pub password: Option<String>,
+    /// `SUPERUSER` or `NOSUPERUSER`
+    pub superuser: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropUser {
+    pub if_exists: bool,
+    pub name: Identifier,
+}
+
+/// `GRANT role TO grantee`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GrantRole {
+    pub role: Identifier,
+    pub grantee: Identifier,
+}
+
+/// `REVOKE role FROM revokee`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RevokeRole {
+    pub role: Identifier,
+    pub revokee: Identifier,
+}
+
+/// `GRANT permission ON resource TO grantee`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GrantPermission {
+    pub permission: Permission,
+    pub resource: Resource,
+    pub grantee: Identifier,
+}
+
+/// `REVOKE permission ON resource FROM revokee`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RevokePermission {
+    pub permission: Permission,
+    pub resource: Resource,
+    pub revokee: Identifier,
+}
+
+/// `LIST ROLES [OF role] [NORECURSIVE]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListRoles {
+    pub of: Option<Identifier>,
+    /// False when `NORECURSIVE` is given
+    pub recursive: bool,
+}
+
+/// `LIST permission [ON resource] [OF role] [NORECURSIVE]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListPermissions {
+    pub permission: Permission,
+    pub resource: Option<Resource>,
+    pub of: Option<Identifier>,
+    /// False when `NORECURSIVE` is given
+    pub recursive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Permission {
+    /// `ALL [PERMISSIONS]`
+    All,
+    Create,
+    Alter,
+    Drop,
+    Select,
+    Modify,
+    Authorize,
+    Describe,
+    Execute,
+    Unmask,
+    SelectMasked,
+}
 
+/// The resource a permission is granted on
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Resource {
+    /// `ALL KEYSPACES`
+    AllKeyspaces,
+    /// `KEYSPACE ks`
+    Keyspace(Identifier),
+    /// `[TABLE] ks.t`
+    Table(QualifiedName),
+    /// `ALL ROLES`
+    AllRoles,
+    /// `ROLE r`
+    Role(Identifier),
+    /// `ALL FUNCTIONS`
+    AllFunctions,
+    /// `ALL FUNCTIONS IN KEYSPACE ks`
+    AllFunctionsInKeyspace(Identifier),
+    /// `FUNCTION ks.f(type, ...)`
+    Function {
+        name: QualifiedName,
+        argument_types: Vec<CqlType>,
+    },
+    /// `ALL MBEANS`
+    AllMbeans,
+    /// `MBEAN 'name'`
+    Mbean(String),
+    /// `MBEANS 'pattern'`
+    Mbeans(String),
+}
+
+/// `DESCRIBE target [WITH INTERNALS]`, executed server side since Cassandra 4
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Describe {
+    pub target: DescribeTarget,
+    pub with_internals: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum DescribeTarget {
+    /// `CLUSTER`
+    Cluster,
+    /// `[FULL] SCHEMA`
+    Schema { full: bool },
+    /// `KEYSPACES`
+    Keyspaces,
+    /// `[ONLY] KEYSPACE [ks]`, the current keyspace if no name is given
+    Keyspace {
+        only: bool,
+        name: Option<Identifier>,
+    },
+    /// `TABLES`
+    Tables,
+    /// `TABLE ks.t`
+    Table(QualifiedName),
+    /// `INDEX ks.i`
+    Index(QualifiedName),
+    /// `MATERIALIZED VIEW ks.v`
+    MaterializedView(QualifiedName),
+    /// `TYPES`
+    Types,
+    /// `TYPE ks.t`
+    Type(QualifiedName),
+    /// `FUNCTIONS`
+    Functions,
+    /// `FUNCTION ks.f`
+    Function(QualifiedName),
+    /// `AGGREGATES`
+    Aggregates,
+    /// `AGGREGATE ks.a`
+    Aggregate(QualifiedName),
+    /// `DESCRIBE name`, which Cassandra resolves to a keyspace, table, view, index, type, function or aggregate
+    Name(QualifiedName),
+}
+
+/// A field of a user defined type
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldDefinition {
+    pub name: Identifier,
+    pub data_type: CqlType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnDefinition {
+    pub name: Identifier,
+    pub data_type: CqlType,
+    pub static_: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum CqlType {
+    Native(NativeType),
+    List(Box<CqlType>),
+    Set(Box<CqlType>),
+    Map(Box<CqlType>, Box<CqlType>),
+    Tuple(Vec<CqlType>),
+    Frozen(Box<CqlType>),
+    /// A user defined type
+    UserDefined(QualifiedName),
+    /// A custom type given by its java class name, e.g. `'org.apache.cassandra.db.marshal.DateType'`
+    Custom(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum NativeType {
+    Ascii,
+    Bigint,
+    Blob,
+    Boolean,
+    Counter,
+    Date,
+    Decimal,
+    Double,
+    Duration,
+    Float,
+    Inet,
+    Int,
+    Smallint,
+    Text,
+    Time,
+    Timestamp,
+    Timeuuid,
+    Tinyint,
+    Uuid,
+    Varchar,
+    Varint,
+}
+
+/// The `replication` map of a keyspace
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum ReplicationStrategy {
+    /// `{'class': 'SimpleStrategy', 'replication_factor': n}`
+    Simple { replication_factor: u64 },
+    /// `{'class': 'NetworkTopologyStrategy', 'replication_factor': n, 'dc1': n, 'dc2': n}`
+    NetworkTopology {
+        /// The default for datacenters not listed, Cassandra 4.0 and later only
+        replication_factor: Option<u64>,
+        datacenters: Vec<(String, u64)>,
+    },
+    /// Any other strategy class, or options that could not be interpreted, with every value as written
+    Other {
+        class: String,
+        options: Vec<(String, String)>,
+    },
+}
+
+/// A `name = value` option in a `WITH` clause
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Property {
+    pub name: Identifier,
+    pub value: PropertyValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum PropertyValue {
+    Constant(Constant),
+    /// An unquoted value such as `KEYS_ONLY`
+    Identifier(String),
+    Map(Vec<(Constant, Constant)>),
+}
+
+/// The `IF` clause of a lightweight transaction
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Condition {
+    /// `IF EXISTS`
+    Exists,
+    /// `IF NOT EXISTS`
+    NotExists,
+    /// `IF col = x AND m['k'] != y AND c IN (...)`, every element is AND'd together
+    Columns(Vec<RelationElement>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assignment {
+    /// A column name or an element of a collection column
+    pub lhs: Expr,
+    pub operator: AssignmentOperator,
+    pub rhs: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum AssignmentOperator {
+    /// `c = rhs`
+    Set,
+    /// `c = c + rhs` or `c += rhs`
+    Add,
+    /// `c = c - rhs` or `c -= rhs`
+    Subtract,
+    /// `c = rhs + c`
+    Prepend,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Select {
     pub distinct: bool,
     pub json: bool,
     pub select: Vec<SelectElement>,
-    pub from: Vec<String>,
+    pub from: QualifiedName,
     /// Every element is AND'd together
     pub where_: Vec<RelationElement>,
-    pub order_by: Option<OrderBy>,
-    pub limit: Option<u64>,
+    pub group_by: Vec<Identifier>,
+    pub order_by: Vec<OrderBy>,
+    pub per_partition_limit: Option<Limit>,
+    pub limit: Option<Limit>,
     pub allow_filtering: bool,
+    /// ScyllaDB only, can only contain a timeout
+    pub using: Option<UsingClause>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectElement {
     pub expr: Expr,
-    pub as_alias: Option<String>,
+    pub as_alias: Option<Identifier>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum RelationElement {
     Comparison(RelationComparison),
     In(RelationIn),
     Contains(RelationContains),
     ContainsKey(RelationContainsKey),
+    /// `col IS NOT NULL`, only valid when defining a materialized view
+    IsNotNull(RelationIsNotNull),
+}
+
+impl RelationElement {
+    /// The span of the whole relation
+    pub fn span(&self) -> Span {
+        match self {
+            RelationElement::Comparison(comparison) => comparison.span,
+            RelationElement::In(in_) => in_.span,
+            RelationElement::Contains(contains) => contains.span,
+            RelationElement::ContainsKey(contains) => contains.span,
+            RelationElement::IsNotNull(is_not_null) => is_not_null.span,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RelationComparison {
     pub lhs: Expr,
     pub operator: ComparisonOperator,
     pub rhs: Expr,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RelationIn {
-    pub lhs: String,
-    pub rhs: Vec<Expr>,
+    pub lhs: Expr,
+    pub rhs: InValues,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum InValues {
+    /// `IN (1, 2)`
+    List(Vec<Expr>),
+    /// `IN ?`, a single bind marker for the whole list
+    BindMarker(BindMarker, Span),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RelationContains {
-    pub lhs: String,
-    pub rhs: Constant,
+    pub lhs: Identifier,
+    pub rhs: Expr,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RelationContainsKey {
-    pub lhs: String,
-    pub rhs: Constant,
+    pub lhs: Identifier,
+    pub rhs: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RelationIsNotNull {
+    pub column: Identifier,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum ComparisonOperator {
     Equals,
+    NotEquals,
     LessThan,
     LessThanOrEqualTo,
     GreaterThan,
@@ -69,39 +1114,105 @@ pub enum ComparisonOperator {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderBy {
-    pub name: String,
+    pub name: Identifier,
     pub ordering: Ordering,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Ordering {
     Asc,
     Desc,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum Limit {
+    Literal(u64),
+    BindMarker(BindMarker),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
+pub enum BindMarker {
+    /// `?`
+    Anonymous,
+    /// `:name`
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Expr {
-    Name(String),
-    Constant(Constant),
+    Name(Identifier),
+    Constant(Constant, Span),
     FunctionCall(FunctionCall),
-    Wildcard,
+    BindMarker(BindMarker, Span),
+    Subscript(Subscript),
+    List(Vec<Expr>, Span),
+    /// `{}` is parsed as an empty set, as Cassandra does
+    Set(Vec<Expr>, Span),
+    /// Never empty when parsed, see `Set`
+    Map(Vec<(Expr, Expr)>, Span),
+    Tuple(Vec<Expr>, Span),
+    Wildcard(Span),
+}
+
+impl Expr {
+    /// The span of the whole expression
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Name(name) => name.span,
+            Expr::FunctionCall(call) => call.span,
+            Expr::Subscript(subscript) => subscript.span,
+            Expr::Constant(_, span)
+            | Expr::BindMarker(_, span)
+            | Expr::List(_, span)
+            | Expr::Set(_, span)
+            | Expr::Map(_, span)
+            | Expr::Tuple(_, span)
+            | Expr::Wildcard(span) => *span,
+        }
+    }
+}
+
+/// `column[key]`, a single element of a map, list or set column
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Subscript {
+    pub column: Identifier,
+    pub key: Box<Expr>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Constant {
-    UUID,
+    /// A UUID literal such as `123e4567-e89b-12d3-a456-426614174000`, stored as written
+    UUID(String),
     String(String),
     Decimal(i64),
     Float(f64), // TODO: we should store raw instead of ieee
-    Hex(i64),
+    /// A blob literal such as `0xcafe`
+    Hex(Vec<u8>),
     Bool(bool),
+    /// `$$...$$`, the contents are kept verbatim
     CodeBlock(String),
     Null,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionCall {
-    pub function: String,
+    pub function: QualifiedName,
     pub args: Vec<Expr>,
+    pub span: Span,
 }
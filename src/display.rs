@@ -0,0 +1,1466 @@
+use std::cell::Cell;
+use std::fmt::{self, Display, Formatter};
+
+use crate::ast::{self, *};
+
+thread_local! {
+    /// Set while [`with_placeholders`] renders a node, so every constant is written as `?`.
+    static PLACEHOLDERS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Renders `node` with every constant written as `?`, including those where CQL does not accept a bind marker.
+pub(crate) fn with_placeholders(node: &impl Display) -> String {
+    /// Restores the previous setting when dropped, so a panic while rendering or a nested call cannot leave it set.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            PLACEHOLDERS.with(|placeholders| placeholders.set(self.0));
+        }
+    }
+
+    let _restore = Restore(PLACEHOLDERS.with(|placeholders| placeholders.replace(true)));
+    node.to_string()
+}
+
+/// Renders an identifier, quoting it if it would otherwise be lowercased or read as a keyword.
+pub(crate) struct Identifier<'a>(pub &'a str);
+
+impl Display for Identifier<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if is_unquoted_identifier(self.0) && !is_reserved_keyword(self.0) {
+            f.write_str(self.0)
+        } else {
+            write_quoted_identifier(f, self.0)
+        }
+    }
+}
+
+impl Display for ast::Identifier {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Identifier(self).fmt(f)
+    }
+}
+
+/// Renders a function name, which is always followed by `(` so only needs quoting to preserve its case.
+struct FunctionName<'a>(&'a QualifiedName);
+
+impl Display for FunctionName<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let write = |f: &mut Formatter, name: &str| {
+            if is_unquoted_identifier(name) {
+                f.write_str(name)
+            } else {
+                write_quoted_identifier(f, name)
+            }
+        };
+        if let Some(keyspace) = &self.0.keyspace {
+            write(f, keyspace)?;
+            f.write_str(".")?;
+        }
+        write(f, &self.0.name)
+    }
+}
+
+/// Renders a string literal, escaping `'` as `''`.
+struct StringLiteral<'a>(&'a str);
+
+impl Display for StringLiteral<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "'{}'", self.0.replace('\'', "''"))
+    }
+}
+
+fn is_unquoted_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(|chr| matches!(chr, 'a'..='z' | '0'..='9' | '_'))
+}
+
+fn write_quoted_identifier(f: &mut Formatter, name: &str) -> fmt::Result {
+    write!(f, "\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Cassandra's reserved keywords, plus the words this parser reads as a literal or keyword where an identifier could appear.
+fn is_reserved_keyword(name: &str) -> bool {
+    const RESERVED_KEYWORDS: &[&str] = &[
+        "add",
+        "allow",
+        "alter",
+        "and",
+        "apply",
+        "asc",
+        "authorize",
+        "batch",
+        "begin",
+        "by",
+        "columnfamily",
+        "create",
+        "delete",
+        "desc",
+        "describe",
+        "distinct",
+        "drop",
+        "entries",
+        "execute",
+        "false",
+        "from",
+        "full",
+        "grant",
+        "if",
+        "in",
+        "index",
+        "infinity",
+        "insert",
+        "into",
+        "is",
+        "json",
+        "keyspace",
+        "limit",
+        "materialized",
+        "mbean",
+        "mbeans",
+        "modify",
+        "nan",
+        "norecursive",
+        "not",
+        "null",
+        "of",
+        "on",
+        "or",
+        "order",
+        "primary",
+        "rename",
+        "replace",
+        "revoke",
+        "schema",
+        "select",
+        "set",
+        "table",
+        "to",
+        "token",
+        "true",
+        "truncate",
+        "unlogged",
+        "update",
+        "use",
+        "using",
+        "view",
+        "where",
+        "with",
+    ];
+    RESERVED_KEYWORDS.contains(&name)
+}
+
+/// Writes `items` separated by `separator`
+fn join<T: Display>(
+    f: &mut Formatter,
+    items: impl IntoIterator<Item = T>,
+    separator: &str,
+) -> fmt::Result {
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn write_if_exists(f: &mut Formatter, if_exists: bool) -> fmt::Result {
+    if if_exists {
+        f.write_str(" IF EXISTS")?;
+    }
+    Ok(())
+}
+
+fn write_if_not_exists(f: &mut Formatter, if_not_exists: bool) -> fmt::Result {
+    if if_not_exists {
+        f.write_str(" IF NOT EXISTS")?;
+    }
+    Ok(())
+}
+
+fn write_where(f: &mut Formatter, where_: &[RelationElement]) -> fmt::Result {
+    if !where_.is_empty() {
+        f.write_str(" WHERE ")?;
+        join(f, where_, " AND ")?;
+    }
+    Ok(())
+}
+
+fn write_using(f: &mut Formatter, using: &Option<UsingClause>) -> fmt::Result {
+    if let Some(using) = using {
+        write!(f, " {}", using)?;
+    }
+    Ok(())
+}
+
+fn write_condition(f: &mut Formatter, condition: &Option<Condition>) -> fmt::Result {
+    if let Some(condition) = condition {
+        write!(f, " {}", condition)?;
+    }
+    Ok(())
+}
+
+fn write_map(f: &mut Formatter, entries: &[(Constant, Constant)]) -> fmt::Result {
+    f.write_str("{")?;
+    join(
+        f,
+        entries
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value)),
+        ", ",
+    )?;
+    f.write_str("}")
+}
+
+/// Renders a `PRIMARY KEY (...)` clause from a partition key and clustering key.
+pub(crate) struct PrimaryKey<'a>(pub &'a [ast::Identifier], pub &'a [ast::Identifier]);
+
+impl Display for PrimaryKey<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let PrimaryKey(partition_key, clustering_key) = self;
+        f.write_str("PRIMARY KEY (")?;
+        if partition_key.len() == 1 {
+            write!(f, "{}", Identifier(&partition_key[0]))?;
+        } else {
+            f.write_str("(")?;
+            join(f, *partition_key, ", ")?;
+            f.write_str(")")?;
+        }
+        for column in *clustering_key {
+            write!(f, ", {}", Identifier(column))?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Renders a `CLUSTERING ORDER BY (...)` table option.
+pub(crate) struct ClusteringOrder<'a>(pub &'a [OrderBy]);
+
+impl Display for ClusteringOrder<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CLUSTERING ORDER BY (")?;
+        join(f, self.0, ", ")?;
+        f.write_str(")")
+    }
+}
+
+fn write_table_options(
+    f: &mut Formatter,
+    clustering_order: &[OrderBy],
+    compact_storage: bool,
+    options: &[Property],
+) -> fmt::Result {
+    let mut all_options = vec![];
+    if !clustering_order.is_empty() {
+        all_options.push(ClusteringOrder(clustering_order).to_string());
+    }
+    if compact_storage {
+        all_options.push("COMPACT STORAGE".to_string());
+    }
+    all_options.extend(options.iter().map(Property::to_string));
+    if !all_options.is_empty() {
+        f.write_str(" WITH ")?;
+        join(f, all_options, " AND ")?;
+    }
+    Ok(())
+}
+
+fn write_argument_types(f: &mut Formatter, argument_types: &[CqlType]) -> fmt::Result {
+    f.write_str("(")?;
+    join(f, argument_types, ", ")?;
+    f.write_str(")")
+}
+
+fn write_list_options(
+    f: &mut Formatter,
+    of: &Option<ast::Identifier>,
+    recursive: bool,
+) -> fmt::Result {
+    if let Some(of) = of {
+        write!(f, " OF {}", Identifier(of))?;
+    }
+    if !recursive {
+        f.write_str(" NORECURSIVE")?;
+    }
+    Ok(())
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Statement::Select(select) => select.fmt(f),
+            Statement::Insert(insert) => insert.fmt(f),
+            Statement::Update(update) => update.fmt(f),
+            Statement::Delete(delete) => delete.fmt(f),
+            Statement::Batch(batch) => batch.fmt(f),
+            Statement::CreateKeyspace(create) => create.fmt(f),
+            Statement::AlterKeyspace(alter) => alter.fmt(f),
+            Statement::DropKeyspace(drop) => drop.fmt(f),
+            Statement::CreateTable(create) => create.fmt(f),
+            Statement::AlterTable(alter) => alter.fmt(f),
+            Statement::DropTable(drop) => drop.fmt(f),
+            Statement::Truncate(truncate) => truncate.fmt(f),
+            Statement::CreateIndex(create) => create.fmt(f),
+            Statement::DropIndex(drop) => drop.fmt(f),
+            Statement::CreateMaterializedView(create) => create.fmt(f),
+            Statement::AlterMaterializedView(alter) => alter.fmt(f),
+            Statement::DropMaterializedView(drop) => drop.fmt(f),
+            Statement::CreateType(create) => create.fmt(f),
+            Statement::AlterType(alter) => alter.fmt(f),
+            Statement::DropType(drop) => drop.fmt(f),
+            Statement::CreateFunction(create) => create.fmt(f),
+            Statement::DropFunction(drop) => drop.fmt(f),
+            Statement::CreateAggregate(create) => create.fmt(f),
+            Statement::DropAggregate(drop) => drop.fmt(f),
+            Statement::CreateTrigger(create) => create.fmt(f),
+            Statement::DropTrigger(drop) => drop.fmt(f),
+            Statement::CreateRole(create) => create.fmt(f),
+            Statement::AlterRole(alter) => alter.fmt(f),
+            Statement::DropRole(drop) => drop.fmt(f),
+            Statement::CreateUser(create) => create.fmt(f),
+            Statement::AlterUser(alter) => alter.fmt(f),
+            Statement::DropUser(drop) => drop.fmt(f),
+            Statement::GrantRole(grant) => grant.fmt(f),
+            Statement::RevokeRole(revoke) => revoke.fmt(f),
+            Statement::GrantPermission(grant) => grant.fmt(f),
+            Statement::RevokePermission(revoke) => revoke.fmt(f),
+            Statement::ListRoles(list) => list.fmt(f),
+            Statement::ListUsers => f.write_str("LIST USERS"),
+            Statement::ListPermissions(list) => list.fmt(f),
+            Statement::Use(keyspace) => write!(f, "USE {}", Identifier(keyspace)),
+            Statement::Describe(describe) => describe.fmt(f),
+        }
+    }
+}
+
+impl Display for ScriptElement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ScriptElement::Statement(statement) => statement.fmt(f),
+            ScriptElement::ShellCommand(command) => command.fmt(f),
+        }
+    }
+}
+
+impl Display for ShellCommand {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let switch = |on: &bool| if *on { " ON" } else { " OFF" };
+        match self {
+            ShellCommand::Consistency(level) => {
+                f.write_str("CONSISTENCY")?;
+                if let Some(level) = level {
+                    write!(f, " {}", level)?;
+                }
+                Ok(())
+            }
+            ShellCommand::SerialConsistency(level) => {
+                f.write_str("SERIAL CONSISTENCY")?;
+                if let Some(level) = level {
+                    write!(f, " {}", level)?;
+                }
+                Ok(())
+            }
+            ShellCommand::Tracing(on) => {
+                write!(f, "TRACING{}", on.as_ref().map_or("", switch))
+            }
+            ShellCommand::Paging(paging) => {
+                f.write_str("PAGING")?;
+                match paging {
+                    Some(Paging::On) => f.write_str(" ON"),
+                    Some(Paging::Off) => f.write_str(" OFF"),
+                    Some(Paging::PageSize(size)) => write!(f, " {}", size),
+                    None => Ok(()),
+                }
+            }
+            ShellCommand::Expand(on) => write!(f, "EXPAND{}", on.as_ref().map_or("", switch)),
+            ShellCommand::Source(file) => write!(f, "SOURCE {}", StringLiteral(file)),
+            ShellCommand::Capture(capture) => {
+                f.write_str("CAPTURE")?;
+                match capture {
+                    Some(Capture::File(file)) => write!(f, " {}", StringLiteral(file)),
+                    Some(Capture::Off) => f.write_str(" OFF"),
+                    None => Ok(()),
+                }
+            }
+            ShellCommand::Copy(copy) => copy.fmt(f),
+        }
+    }
+}
+
+impl Display for ConsistencyLevel {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ConsistencyLevel::Any => "ANY",
+            ConsistencyLevel::One => "ONE",
+            ConsistencyLevel::Two => "TWO",
+            ConsistencyLevel::Three => "THREE",
+            ConsistencyLevel::Quorum => "QUORUM",
+            ConsistencyLevel::All => "ALL",
+            ConsistencyLevel::LocalQuorum => "LOCAL_QUORUM",
+            ConsistencyLevel::EachQuorum => "EACH_QUORUM",
+            ConsistencyLevel::Serial => "SERIAL",
+            ConsistencyLevel::LocalSerial => "LOCAL_SERIAL",
+            ConsistencyLevel::LocalOne => "LOCAL_ONE",
+        })
+    }
+}
+
+impl Display for Copy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "COPY {}", self.table)?;
+        if !self.columns.is_empty() {
+            f.write_str(" (")?;
+            join(f, &self.columns, ", ")?;
+            f.write_str(")")?;
+        }
+        match (self.direction, &self.file) {
+            (CopyDirection::To, Some(file)) => write!(f, " TO {}", StringLiteral(file))?,
+            (CopyDirection::To, None) => f.write_str(" TO STDOUT")?,
+            (CopyDirection::From, Some(file)) => write!(f, " FROM {}", StringLiteral(file))?,
+            (CopyDirection::From, None) => f.write_str(" FROM STDIN")?,
+        }
+        if !self.options.is_empty() {
+            f.write_str(" WITH ")?;
+            join(f, &self.options, " AND ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for QualifiedName {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(keyspace) = &self.keyspace {
+            write!(f, "{}.", Identifier(keyspace))?;
+        }
+        Identifier(&self.name).fmt(f)
+    }
+}
+
+impl Display for Select {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("SELECT ")?;
+        if self.json {
+            f.write_str("JSON ")?;
+        }
+        if self.distinct {
+            f.write_str("DISTINCT ")?;
+        }
+        join(f, &self.select, ", ")?;
+        write!(f, " FROM {}", self.from)?;
+        write_where(f, &self.where_)?;
+        if !self.group_by.is_empty() {
+            f.write_str(" GROUP BY ")?;
+            join(f, &self.group_by, ", ")?;
+        }
+        if !self.order_by.is_empty() {
+            f.write_str(" ORDER BY ")?;
+            join(f, &self.order_by, ", ")?;
+        }
+        if let Some(limit) = &self.per_partition_limit {
+            write!(f, " PER PARTITION LIMIT {}", limit)?;
+        }
+        if let Some(limit) = &self.limit {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        if self.allow_filtering {
+            f.write_str(" ALLOW FILTERING")?;
+        }
+        write_using(f, &self.using)
+    }
+}
+
+impl Display for SelectElement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.expr.fmt(f)?;
+        if let Some(alias) = &self.as_alias {
+            write!(f, " AS {}", Identifier(alias))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Insert {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "INSERT INTO {} (", self.table)?;
+        join(f, &self.columns, ", ")?;
+        f.write_str(") VALUES (")?;
+        join(f, &self.values, ", ")?;
+        f.write_str(")")?;
+        write_condition(f, &self.condition)?;
+        write_using(f, &self.using)
+    }
+}
+
+impl Display for Update {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "UPDATE {}", self.table)?;
+        write_using(f, &self.using)?;
+        f.write_str(" SET ")?;
+        join(f, &self.set, ", ")?;
+        write_where(f, &self.where_)?;
+        write_condition(f, &self.condition)
+    }
+}
+
+impl Display for Delete {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DELETE ")?;
+        if !self.columns.is_empty() {
+            join(f, &self.columns, ", ")?;
+            f.write_str(" ")?;
+        }
+        write!(f, "FROM {}", self.table)?;
+        write_using(f, &self.using)?;
+        write_where(f, &self.where_)?;
+        write_condition(f, &self.condition)
+    }
+}
+
+impl Display for Batch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self.batch_type {
+            BatchType::Logged => "BEGIN BATCH",
+            BatchType::Unlogged => "BEGIN UNLOGGED BATCH",
+            BatchType::Counter => "BEGIN COUNTER BATCH",
+        })?;
+        write_using(f, &self.using)?;
+        for statement in &self.statements {
+            write!(f, " {};", statement)?;
+        }
+        f.write_str(" APPLY BATCH")
+    }
+}
+
+impl Display for BatchStatement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            BatchStatement::Insert(insert) => insert.fmt(f),
+            BatchStatement::Update(update) => update.fmt(f),
+            BatchStatement::Delete(delete) => delete.fmt(f),
+        }
+    }
+}
+
+impl Display for UsingClause {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let elements = [
+            ("TTL", &self.ttl),
+            ("TIMESTAMP", &self.timestamp),
+            ("TIMEOUT", &self.timeout),
+        ]
+        .into_iter()
+        .filter_map(|(keyword, value)| Some(format!("{} {}", keyword, value.as_ref()?)));
+        f.write_str("USING ")?;
+        join(f, elements, " AND ")
+    }
+}
+
+impl Display for UsingValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            UsingValue::Integer(value) => value.fmt(f),
+            UsingValue::Duration(duration) => f.write_str(duration),
+            UsingValue::BindMarker(marker) => marker.fmt(f),
+        }
+    }
+}
+
+impl Display for CreateKeyspace {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE KEYSPACE")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(
+            f,
+            " {} WITH replication = {}",
+            Identifier(&self.name),
+            self.replication
+        )?;
+        if let Some(durable_writes) = self.durable_writes {
+            write!(f, " AND durable_writes = {}", durable_writes)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AlterKeyspace {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("ALTER KEYSPACE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {} WITH ", Identifier(&self.name))?;
+        let options = [
+            self.replication
+                .as_ref()
+                .map(|replication| format!("replication = {}", replication)),
+            self.durable_writes
+                .map(|durable_writes| format!("durable_writes = {}", durable_writes)),
+        ];
+        join(f, options.into_iter().flatten(), " AND ")
+    }
+}
+
+impl Display for DropKeyspace {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP KEYSPACE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", Identifier(&self.name))
+    }
+}
+
+impl Display for ReplicationStrategy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ReplicationStrategy::Simple { replication_factor } => write!(
+                f,
+                "{{'class': 'SimpleStrategy', 'replication_factor': {}}}",
+                replication_factor
+            ),
+            ReplicationStrategy::NetworkTopology {
+                replication_factor,
+                datacenters,
+            } => {
+                f.write_str("{'class': 'NetworkTopologyStrategy'")?;
+                if let Some(replication_factor) = replication_factor {
+                    write!(f, ", 'replication_factor': {}", replication_factor)?;
+                }
+                for (datacenter, factor) in datacenters {
+                    write!(f, ", {}: {}", StringLiteral(datacenter), factor)?;
+                }
+                f.write_str("}")
+            }
+            ReplicationStrategy::Other { class, options } => {
+                write!(f, "{{'class': {}", StringLiteral(class))?;
+                for (name, value) in options {
+                    write!(f, ", {}: {}", StringLiteral(name), StringLiteral(value))?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl Display for CreateTable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE TABLE")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {} (", self.name)?;
+        for column in &self.columns {
+            write!(f, "{}, ", column)?;
+        }
+        PrimaryKey(&self.partition_key, &self.clustering_key).fmt(f)?;
+        f.write_str(")")?;
+        write_table_options(
+            f,
+            &self.clustering_order,
+            self.compact_storage,
+            &self.options,
+        )
+    }
+}
+
+impl Display for ColumnDefinition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}", Identifier(&self.name), self.data_type)?;
+        if self.static_ {
+            f.write_str(" STATIC")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AlterTable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("ALTER TABLE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {} {}", self.name, self.operation)
+    }
+}
+
+impl Display for AlterTableOperation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AlterTableOperation::Add(columns) if columns.len() == 1 => {
+                write!(f, "ADD {}", columns[0])
+            }
+            AlterTableOperation::Add(columns) => {
+                f.write_str("ADD (")?;
+                join(f, columns, ", ")?;
+                f.write_str(")")
+            }
+            AlterTableOperation::Drop(columns) if columns.len() == 1 => {
+                write!(f, "DROP {}", Identifier(&columns[0]))
+            }
+            AlterTableOperation::Drop(columns) => {
+                f.write_str("DROP (")?;
+                join(f, columns, ", ")?;
+                f.write_str(")")
+            }
+            AlterTableOperation::Rename(renames) => write_renames(f, renames),
+            AlterTableOperation::With(options) => {
+                f.write_str("WITH ")?;
+                join(f, options, " AND ")
+            }
+        }
+    }
+}
+
+fn write_renames(f: &mut Formatter, renames: &[(ast::Identifier, ast::Identifier)]) -> fmt::Result {
+    f.write_str("RENAME ")?;
+    join(
+        f,
+        renames
+            .iter()
+            .map(|(from, to)| format!("{} TO {}", Identifier(from), Identifier(to))),
+        " AND ",
+    )
+}
+
+impl Display for DropTable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP TABLE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", self.name)
+    }
+}
+
+impl Display for Truncate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "TRUNCATE {}", self.name)
+    }
+}
+
+impl Display for CreateIndex {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(if self.custom {
+            "CREATE CUSTOM INDEX"
+        } else {
+            "CREATE INDEX"
+        })?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        if let Some(name) = &self.name {
+            write!(f, " {}", Identifier(name))?;
+        }
+        write!(f, " ON {} (", self.table)?;
+        join(f, &self.targets, ", ")?;
+        f.write_str(")")?;
+        if let Some(using) = &self.using {
+            write!(f, " USING {}", StringLiteral(using))?;
+        }
+        if !self.options.is_empty() {
+            f.write_str(" WITH OPTIONS = ")?;
+            write_map(f, &self.options)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for IndexTarget {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            IndexTarget::Column(column) => Identifier(column).fmt(f),
+            IndexTarget::Keys(column) => write!(f, "KEYS({})", Identifier(column)),
+            IndexTarget::Values(column) => write!(f, "VALUES({})", Identifier(column)),
+            IndexTarget::Entries(column) => write!(f, "ENTRIES({})", Identifier(column)),
+            IndexTarget::Full(column) => write!(f, "FULL({})", Identifier(column)),
+        }
+    }
+}
+
+impl Display for DropIndex {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP INDEX")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", self.name)
+    }
+}
+
+impl Display for CreateMaterializedView {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE MATERIALIZED VIEW")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {} AS {} ", self.name, self.select)?;
+        PrimaryKey(&self.partition_key, &self.clustering_key).fmt(f)?;
+        write_table_options(f, &self.clustering_order, false, &self.options)
+    }
+}
+
+impl Display for AlterMaterializedView {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("ALTER MATERIALIZED VIEW")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {} WITH ", self.name)?;
+        join(f, &self.options, " AND ")
+    }
+}
+
+impl Display for DropMaterializedView {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP MATERIALIZED VIEW")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", self.name)
+    }
+}
+
+impl Display for CreateType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE TYPE")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {} (", self.name)?;
+        join(f, &self.fields, ", ")?;
+        f.write_str(")")
+    }
+}
+
+impl Display for FieldDefinition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}", Identifier(&self.name), self.data_type)
+    }
+}
+
+impl Display for AlterType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("ALTER TYPE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {} ", self.name)?;
+        match &self.operation {
+            AlterTypeOperation::Add(field) => write!(f, "ADD {}", field),
+            AlterTypeOperation::Rename(renames) => write_renames(f, renames),
+        }
+    }
+}
+
+impl Display for DropType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP TYPE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", self.name)
+    }
+}
+
+impl Display for CreateFunction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(if self.or_replace {
+            "CREATE OR REPLACE FUNCTION"
+        } else {
+            "CREATE FUNCTION"
+        })?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {} (", self.name)?;
+        join(
+            f,
+            self.arguments
+                .iter()
+                .map(|argument| format!("{} {}", Identifier(&argument.name), argument.data_type)),
+            ", ",
+        )?;
+        f.write_str(if self.called_on_null_input {
+            ") CALLED ON NULL INPUT"
+        } else {
+            ") RETURNS NULL ON NULL INPUT"
+        })?;
+        write!(
+            f,
+            " RETURNS {} LANGUAGE {} AS {}",
+            self.return_type,
+            Identifier(&self.language),
+            self.body
+        )
+    }
+}
+
+impl Display for DropFunction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP FUNCTION")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", self.name)?;
+        if let Some(argument_types) = &self.argument_types {
+            write_argument_types(f, argument_types)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CreateAggregate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(if self.or_replace {
+            "CREATE OR REPLACE AGGREGATE"
+        } else {
+            "CREATE AGGREGATE"
+        })?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {} ", self.name)?;
+        write_argument_types(f, &self.argument_types)?;
+        write!(
+            f,
+            " SFUNC {} STYPE {}",
+            Identifier(&self.state_function),
+            self.state_type
+        )?;
+        if let Some(final_function) = &self.final_function {
+            write!(f, " FINALFUNC {}", Identifier(final_function))?;
+        }
+        if let Some(initial_condition) = &self.initial_condition {
+            write!(f, " INITCOND {}", initial_condition)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DropAggregate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP AGGREGATE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", self.name)?;
+        if let Some(argument_types) = &self.argument_types {
+            write_argument_types(f, argument_types)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CreateTrigger {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE TRIGGER")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(
+            f,
+            " {} ON {} USING {}",
+            Identifier(&self.name),
+            self.table,
+            StringLiteral(&self.using)
+        )
+    }
+}
+
+impl Display for DropTrigger {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP TRIGGER")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {} ON {}", Identifier(&self.name), self.table)
+    }
+}
+
+impl Display for CreateRole {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE ROLE")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {}", Identifier(&self.name))?;
+        if !self.options.is_empty() {
+            f.write_str(" WITH ")?;
+            join(f, &self.options, " AND ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AlterRole {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "ALTER ROLE {}", Identifier(&self.name))?;
+        if !self.options.is_empty() {
+            f.write_str(" WITH ")?;
+            join(f, &self.options, " AND ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DropRole {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP ROLE")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", Identifier(&self.name))
+    }
+}
+
+impl Display for RoleOption {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RoleOption::Password(password) => write!(f, "PASSWORD = {}", StringLiteral(password)),
+            RoleOption::HashedPassword(hash) => {
+                write!(f, "HASHED PASSWORD = {}", StringLiteral(hash))
+            }
+            RoleOption::Login(login) => write!(f, "LOGIN = {}", login),
+            RoleOption::Superuser(superuser) => write!(f, "SUPERUSER = {}", superuser),
+            RoleOption::Options(options) => {
+                f.write_str("OPTIONS = ")?;
+                write_map(f, options)
+            }
+            RoleOption::AccessToDatacenters(datacenters) => {
+                f.write_str("ACCESS TO DATACENTERS {")?;
+                join(f, datacenters.iter().map(|dc| StringLiteral(dc)), ", ")?;
+                f.write_str("}")
+            }
+            RoleOption::AccessToAllDatacenters => f.write_str("ACCESS TO ALL DATACENTERS"),
+        }
+    }
+}
+
+fn write_user_options(
+    f: &mut Formatter,
+    password: &Option<String>,
+    superuser: Option<bool>,
+) -> fmt::Result {
+    if let Some(password) = password {
+        write!(f, " WITH PASSWORD {}", StringLiteral(password))?;
+    }
+    match superuser {
+        Some(true) => f.write_str(" SUPERUSER"),
+        Some(false) => f.write_str(" NOSUPERUSER"),
+        None => Ok(()),
+    }
+}
+
+impl Display for CreateUser {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CREATE USER")?;
+        write_if_not_exists(f, self.if_not_exists)?;
+        write!(f, " {}", Identifier(&self.name))?;
+        write_user_options(f, &self.password, self.superuser)
+    }
+}
+
+impl Display for AlterUser {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "ALTER USER {}", Identifier(&self.name))?;
+        write_user_options(f, &self.password, self.superuser)
+    }
+}
+
+impl Display for DropUser {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("DROP USER")?;
+        write_if_exists(f, self.if_exists)?;
+        write!(f, " {}", Identifier(&self.name))
+    }
+}
+
+impl Display for GrantRole {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "GRANT {} TO {}",
+            Identifier(&self.role),
+            Identifier(&self.grantee)
+        )
+    }
+}
+
+impl Display for RevokeRole {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "REVOKE {} FROM {}",
+            Identifier(&self.role),
+            Identifier(&self.revokee)
+        )
+    }
+}
+
+impl Display for GrantPermission {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "GRANT {} ON {} TO {}",
+            self.permission,
+            self.resource,
+            Identifier(&self.grantee)
+        )
+    }
+}
+
+impl Display for RevokePermission {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "REVOKE {} ON {} FROM {}",
+            self.permission,
+            self.resource,
+            Identifier(&self.revokee)
+        )
+    }
+}
+
+impl Display for ListRoles {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("LIST ROLES")?;
+        write_list_options(f, &self.of, self.recursive)
+    }
+}
+
+impl Display for ListPermissions {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "LIST {}", self.permission)?;
+        if let Some(resource) = &self.resource {
+            write!(f, " ON {}", resource)?;
+        }
+        write_list_options(f, &self.of, self.recursive)
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Permission::All => "ALL PERMISSIONS",
+            Permission::Create => "CREATE",
+            Permission::Alter => "ALTER",
+            Permission::Drop => "DROP",
+            Permission::Select => "SELECT",
+            Permission::Modify => "MODIFY",
+            Permission::Authorize => "AUTHORIZE",
+            Permission::Describe => "DESCRIBE",
+            Permission::Execute => "EXECUTE",
+            Permission::Unmask => "UNMASK",
+            Permission::SelectMasked => "SELECT_MASKED",
+        })
+    }
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Resource::AllKeyspaces => f.write_str("ALL KEYSPACES"),
+            Resource::Keyspace(keyspace) => write!(f, "KEYSPACE {}", Identifier(keyspace)),
+            Resource::Table(table) => write!(f, "TABLE {}", table),
+            Resource::AllRoles => f.write_str("ALL ROLES"),
+            Resource::Role(role) => write!(f, "ROLE {}", Identifier(role)),
+            Resource::AllFunctions => f.write_str("ALL FUNCTIONS"),
+            Resource::AllFunctionsInKeyspace(keyspace) => {
+                write!(f, "ALL FUNCTIONS IN KEYSPACE {}", Identifier(keyspace))
+            }
+            Resource::Function {
+                name,
+                argument_types,
+            } => {
+                write!(f, "FUNCTION {}", name)?;
+                write_argument_types(f, argument_types)
+            }
+            Resource::AllMbeans => f.write_str("ALL MBEANS"),
+            Resource::Mbean(name) => write!(f, "MBEAN {}", StringLiteral(name)),
+            Resource::Mbeans(pattern) => write!(f, "MBEANS {}", StringLiteral(pattern)),
+        }
+    }
+}
+
+impl Display for Describe {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "DESCRIBE {}", self.target)?;
+        if self.with_internals {
+            f.write_str(" WITH INTERNALS")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DescribeTarget {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            DescribeTarget::Cluster => f.write_str("CLUSTER"),
+            DescribeTarget::Schema { full: true } => f.write_str("FULL SCHEMA"),
+            DescribeTarget::Schema { full: false } => f.write_str("SCHEMA"),
+            DescribeTarget::Keyspaces => f.write_str("KEYSPACES"),
+            DescribeTarget::Keyspace { only, name } => {
+                if *only {
+                    f.write_str("ONLY ")?;
+                }
+                f.write_str("KEYSPACE")?;
+                if let Some(name) = name {
+                    write!(f, " {}", Identifier(name))?;
+                }
+                Ok(())
+            }
+            DescribeTarget::Tables => f.write_str("TABLES"),
+            DescribeTarget::Table(name) => write!(f, "TABLE {}", name),
+            DescribeTarget::Index(name) => write!(f, "INDEX {}", name),
+            DescribeTarget::MaterializedView(name) => write!(f, "MATERIALIZED VIEW {}", name),
+            DescribeTarget::Types => f.write_str("TYPES"),
+            DescribeTarget::Type(name) => write!(f, "TYPE {}", name),
+            DescribeTarget::Functions => f.write_str("FUNCTIONS"),
+            DescribeTarget::Function(name) => write!(f, "FUNCTION {}", name),
+            DescribeTarget::Aggregates => f.write_str("AGGREGATES"),
+            DescribeTarget::Aggregate(name) => write!(f, "AGGREGATE {}", name),
+            // A bare name that is also a describe keyword must be quoted to be read as a name
+            DescribeTarget::Name(QualifiedName {
+                keyspace: None,
+                name,
+                ..
+            }) if matches!(
+                name.as_str(),
+                "cluster"
+                    | "full"
+                    | "schema"
+                    | "keyspaces"
+                    | "only"
+                    | "tables"
+                    | "types"
+                    | "type"
+                    | "functions"
+                    | "function"
+                    | "aggregates"
+                    | "aggregate"
+            ) =>
+            {
+                write_quoted_identifier(f, name)
+            }
+            DescribeTarget::Name(name) => name.fmt(f),
+        }
+    }
+}
+
+impl Display for CqlType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CqlType::Native(native) => native.fmt(f),
+            CqlType::List(inner) => write!(f, "list<{}>", inner),
+            CqlType::Set(inner) => write!(f, "set<{}>", inner),
+            CqlType::Map(key, value) => write!(f, "map<{}, {}>", key, value),
+            CqlType::Tuple(types) => {
+                f.write_str("tuple<")?;
+                join(f, types, ", ")?;
+                f.write_str(">")
+            }
+            CqlType::Frozen(inner) => write!(f, "frozen<{}>", inner),
+            CqlType::UserDefined(name) => name.fmt(f),
+            CqlType::Custom(class) => StringLiteral(class).fmt(f),
+        }
+    }
+}
+
+impl Display for NativeType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            NativeType::Ascii => "ascii",
+            NativeType::Bigint => "bigint",
+            NativeType::Blob => "blob",
+            NativeType::Boolean => "boolean",
+            NativeType::Counter => "counter",
+            NativeType::Date => "date",
+            NativeType::Decimal => "decimal",
+            NativeType::Double => "double",
+            NativeType::Duration => "duration",
+            NativeType::Float => "float",
+            NativeType::Inet => "inet",
+            NativeType::Int => "int",
+            NativeType::Smallint => "smallint",
+            NativeType::Text => "text",
+            NativeType::Time => "time",
+            NativeType::Timestamp => "timestamp",
+            NativeType::Timeuuid => "timeuuid",
+            NativeType::Tinyint => "tinyint",
+            NativeType::Uuid => "uuid",
+            NativeType::Varchar => "varchar",
+            NativeType::Varint => "varint",
+        })
+    }
+}
+
+impl Display for Property {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} = {}", Identifier(&self.name), self.value)
+    }
+}
+
+impl Display for PropertyValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PropertyValue::Constant(constant) => constant.fmt(f),
+            PropertyValue::Identifier(identifier) => Identifier(identifier).fmt(f),
+            PropertyValue::Map(entries) => write_map(f, entries),
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Condition::Exists => f.write_str("IF EXISTS"),
+            Condition::NotExists => f.write_str("IF NOT EXISTS"),
+            Condition::Columns(relations) => {
+                f.write_str("IF ")?;
+                join(f, relations, " AND ")
+            }
+        }
+    }
+}
+
+impl Display for Assignment {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (&self.operator, &self.lhs) {
+            (AssignmentOperator::Set, lhs) => write!(f, "{} = {}", lhs, self.rhs),
+            (AssignmentOperator::Add, Expr::Name(_)) => {
+                write!(f, "{} = {} + {}", self.lhs, self.lhs, self.rhs)
+            }
+            (AssignmentOperator::Subtract, Expr::Name(_)) => {
+                write!(f, "{} = {} - {}", self.lhs, self.lhs, self.rhs)
+            }
+            (AssignmentOperator::Prepend, lhs) => write!(f, "{} = {} + {}", lhs, self.rhs, lhs),
+            (AssignmentOperator::Add, lhs) => write!(f, "{} += {}", lhs, self.rhs),
+            (AssignmentOperator::Subtract, lhs) => write!(f, "{} -= {}", lhs, self.rhs),
+        }
+    }
+}
+
+impl Display for InValues {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            InValues::List(values) => {
+                f.write_str("(")?;
+                join(f, values, ", ")?;
+                f.write_str(")")
+            }
+            InValues::BindMarker(marker, _) => marker.fmt(f),
+        }
+    }
+}
+
+impl Display for RelationElement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RelationElement::Comparison(comparison) => write!(
+                f,
+                "{} {} {}",
+                comparison.lhs, comparison.operator, comparison.rhs
+            ),
+            RelationElement::In(in_) => write!(f, "{} IN {}", in_.lhs, in_.rhs),
+            RelationElement::Contains(contains) => {
+                write!(f, "{} CONTAINS {}", Identifier(&contains.lhs), contains.rhs)
+            }
+            RelationElement::ContainsKey(contains) => write!(
+                f,
+                "{} CONTAINS KEY {}",
+                Identifier(&contains.lhs),
+                contains.rhs
+            ),
+            RelationElement::IsNotNull(is_not_null) => {
+                write!(f, "{} IS NOT NULL", is_not_null.column)
+            }
+        }
+    }
+}
+
+impl Display for ComparisonOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ComparisonOperator::Equals => "=",
+            ComparisonOperator::NotEquals => "!=",
+            ComparisonOperator::LessThan => "<",
+            ComparisonOperator::LessThanOrEqualTo => "<=",
+            ComparisonOperator::GreaterThan => ">",
+            ComparisonOperator::GreaterThanOrEqualTo => ">=",
+        })
+    }
+}
+
+impl Display for OrderBy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}", Identifier(&self.name), self.ordering)
+    }
+}
+
+impl Display for Ordering {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Ordering::Asc => "ASC",
+            Ordering::Desc => "DESC",
+        })
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Limit::Literal(limit) => limit.fmt(f),
+            Limit::BindMarker(marker) => marker.fmt(f),
+        }
+    }
+}
+
+impl Display for BindMarker {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            BindMarker::Anonymous => f.write_str("?"),
+            BindMarker::Named(name) => write!(f, ":{}", Identifier(name)),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Expr::Name(name) => Identifier(name).fmt(f),
+            Expr::Constant(constant, _) => constant.fmt(f),
+            Expr::FunctionCall(call) => call.fmt(f),
+            Expr::BindMarker(marker, _) => marker.fmt(f),
+            Expr::Subscript(subscript) => {
+                write!(f, "{}[{}]", Identifier(&subscript.column), subscript.key)
+            }
+            Expr::List(values, _) => {
+                f.write_str("[")?;
+                join(f, values, ", ")?;
+                f.write_str("]")
+            }
+            Expr::Set(values, _) => {
+                f.write_str("{")?;
+                join(f, values, ", ")?;
+                f.write_str("}")
+            }
+            Expr::Map(entries, _) => {
+                f.write_str("{")?;
+                join(
+                    f,
+                    entries
+                        .iter()
+                        .map(|(key, value)| format!("{}: {}", key, value)),
+                    ", ",
+                )?;
+                f.write_str("}")
+            }
+            Expr::Tuple(values, _) => {
+                f.write_str("(")?;
+                join(f, values, ", ")?;
+                f.write_str(")")
+            }
+            Expr::Wildcard(_) => f.write_str("*"),
+        }
+    }
+}
+
+impl Display for FunctionCall {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}(", FunctionName(&self.function))?;
+        join(f, &self.args, ", ")?;
+        f.write_str(")")
+    }
+}
+
+impl Display for Constant {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if PLACEHOLDERS.with(Cell::get) {
+            return f.write_str("?");
+        }
+        match self {
+            Constant::UUID(uuid) => f.write_str(uuid),
+            Constant::String(string) => StringLiteral(string).fmt(f),
+            Constant::Decimal(value) => value.fmt(f),
+            Constant::Float(value) if value.is_nan() => f.write_str("NaN"),
+            Constant::Float(value) if value.is_infinite() => f.write_str(if *value > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            }),
+            // Debug always includes a `.` or an exponent, so the value is read back as a float
+            Constant::Float(value) => write!(f, "{:?}", value),
+            Constant::Hex(bytes) => {
+                f.write_str("0x")?;
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            Constant::Bool(value) => value.fmt(f),
+            Constant::CodeBlock(code) => write!(f, "$${}$$", code),
+            Constant::Null => f.write_str("null"),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The input could not be parsed starting at `offset` bytes into the input.
+    Syntax { offset: usize },
+    /// Only returned in strict mode: a SELECT `clause` appeared after `after` but Cassandra requires it to come first.
+    ClauseOutOfOrder {
+        clause: &'static str,
+        after: &'static str,
+        offset: usize,
+    },
+    /// A SELECT `clause` appeared a second time.
+    DuplicateClause { clause: &'static str, offset: usize },
+}
+
+impl ParseError {
+    /// Byte offset into the input where the error was detected.
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::Syntax { offset } => *offset,
+            ParseError::ClauseOutOfOrder { offset, .. } => *offset,
+            ParseError::DuplicateClause { offset, .. } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Syntax { offset } => write!(f, "syntax error at byte {}", offset),
+            ParseError::ClauseOutOfOrder {
+                clause,
+                after,
+                offset,
+            } => write!(
+                f,
+                "{} clause at byte {} must come before {}",
+                clause, offset, after
+            ),
+            ParseError::DuplicateClause { clause, offset } => {
+                write!(
+                    f,
+                    "{} clause at byte {} appears more than once",
+                    clause, offset
+                )
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,145 @@
+use crate::ast::*;
+use crate::display::with_placeholders;
+use crate::visit_mut::{walk_expr_mut, walk_relation_mut, walk_statement_mut, VisitorMut};
+
+/// Returns the shape of `statement` and a hash of it, so that statements differing only in their literal values can be grouped.
+///
+/// The shape is the statement rendered as CQL with every literal, including collection literals, LIMITs and `USING` values,
+/// replaced by `?`, every `IN` list collapsed to `IN (?)`, passwords masked and everything but quoted identifiers and
+/// string literals lowercased. So `SELECT * FROM t WHERE id = 1` and `select * from T where ID = 2` both give `select * from t where id = ?`.
+///
+/// The hash is the 64 bit FNV-1a hash of the shape, which unlike `std`'s hashers is fixed, so it is stable across Rust and crate versions
+/// for as long as the shape is.
+pub fn fingerprint(statement: &Statement) -> (String, u64) {
+    let mut statement = statement.clone();
+    Normalize.visit_statement_mut(&mut statement);
+    let shape = lowercase(&with_placeholders(&statement));
+    let hash = fnv1a(shape.as_bytes());
+    (shape, hash)
+}
+
+/// Replaces every literal with a bind marker, so a collection of constants becomes a single `?`.
+/// Constants where the grammar does not allow a bind marker, such as property values, are left to [`with_placeholders`].
+struct Normalize;
+
+impl VisitorMut for Normalize {
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        *statement = statement.redacted();
+        match statement {
+            Statement::Select(select) => {
+                normalize_limit(&mut select.per_partition_limit);
+                normalize_limit(&mut select.limit);
+                normalize_using(&mut select.using);
+            }
+            Statement::Insert(Insert { using, .. })
+            | Statement::Update(Update { using, .. })
+            | Statement::Delete(Delete { using, .. }) => normalize_using(using),
+            Statement::Batch(batch) => {
+                normalize_using(&mut batch.using);
+                for statement in &mut batch.statements {
+                    match statement {
+                        BatchStatement::Insert(Insert { using, .. })
+                        | BatchStatement::Update(Update { using, .. })
+                        | BatchStatement::Delete(Delete { using, .. }) => normalize_using(using),
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_relation_mut(&mut self, relation: &mut RelationElement) {
+        if let RelationElement::In(RelationIn {
+            rhs: InValues::List(values),
+            ..
+        }) = relation
+        {
+            *values = vec![Expr::BindMarker(BindMarker::Anonymous, Span::default())];
+        }
+        walk_relation_mut(self, relation);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if is_literal(expr) {
+            *expr = Expr::BindMarker(BindMarker::Anonymous, expr.span());
+        }
+        walk_expr_mut(self, expr);
+    }
+}
+
+/// Returns true for a constant or a collection made up only of constants.
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Constant(..) => true,
+        Expr::List(values, _) | Expr::Set(values, _) | Expr::Tuple(values, _) => {
+            values.iter().all(is_literal)
+        }
+        Expr::Map(entries, _) => entries
+            .iter()
+            .all(|(key, value)| is_literal(key) && is_literal(value)),
+        _ => false,
+    }
+}
+
+fn normalize_limit(limit: &mut Option<Limit>) {
+    if let Some(limit @ Limit::Literal(_)) = limit {
+        *limit = Limit::BindMarker(BindMarker::Anonymous);
+    }
+}
+
+fn normalize_using(using: &mut Option<UsingClause>) {
+    if let Some(using) = using {
+        for value in [&mut using.ttl, &mut using.timestamp, &mut using.timeout]
+            .into_iter()
+            .flatten()
+        {
+            if !matches!(value, UsingValue::BindMarker(_)) {
+                *value = UsingValue::BindMarker(BindMarker::Anonymous);
+            }
+        }
+    }
+}
+
+/// Lowercases `cql` outside of quoted identifiers and string literals.
+fn lowercase(cql: &str) -> String {
+    let mut lowercased = String::with_capacity(cql.len());
+    let mut rest = cql;
+    while let Some(chr) = rest.chars().next() {
+        let len = match chr {
+            '"' | '\'' => {
+                let len = quoted_len(rest, chr);
+                lowercased.push_str(&rest[..len]);
+                len
+            }
+            _ => {
+                lowercased.extend(chr.to_lowercase());
+                chr.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+    lowercased
+}
+
+/// Returns the length of the quoted text at the start of `cql`, where `quote` is escaped by doubling it.
+fn quoted_len(cql: &str, quote: char) -> usize {
+    let mut len = 1;
+    while let Some(found) = cql[len..].find(quote) {
+        len += found + 1;
+        if !cql[len..].starts_with(quote) {
+            return len;
+        }
+        len += 1;
+    }
+    cql.len()
+}
+
+/// The 64 bit FNV-1a hash, see <http://www.isthe.com/chongo/tech/comp/fnv/>
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
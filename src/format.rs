@@ -0,0 +1,629 @@
+use std::fmt::Display;
+
+use crate::ast::*;
+use crate::display::{ClusteringOrder, Identifier, PrimaryKey};
+use crate::{parse_script_with_gaps, ParseError, ParseOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FormatOptions {
+    /// The case keywords are written in. Identifiers, literals and type names are never changed.
+    pub keyword_case: KeywordCase,
+    /// Number of spaces each level of nesting is indented by.
+    pub indent: usize,
+    /// A statement or clause is kept on one line while it fits within this many characters.
+    pub max_width: usize,
+    /// Always put each selector of a multi-column SELECT on its own line.
+    pub one_column_per_line: bool,
+    /// Always put each CREATE TABLE option on its own line, with the `AND`s under `WITH` and the `=` of each property lined up.
+    pub align_with_options: bool,
+    /// Keep comments, whether between statements or within them. Only takes effect in [`format_script`].
+    pub preserve_comments: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            keyword_case: KeywordCase::Upper,
+            indent: 4,
+            max_width: 100,
+            one_column_per_line: false,
+            align_with_options: false,
+            preserve_comments: true,
+        }
+    }
+}
+
+/// Formats a single statement, without a trailing `;`.
+pub fn format_statement(statement: &Statement, options: FormatOptions) -> String {
+    let mut formatter = Formatter::new(options);
+    formatter.statement(statement, 0);
+    formatter.finish(&[])
+}
+
+/// Parses and formats a script, terminating every statement and shell command with `;`.
+///
+/// A comment within a statement is written on its own line, before the line holding the token that follows it.
+/// Single blank lines between statements are kept and longer runs are collapsed.
+pub fn format_script(
+    value: &str,
+    parse_options: ParseOptions,
+    options: FormatOptions,
+) -> Result<String, ParseError> {
+    let (leading, elements) = parse_script_with_gaps(value, parse_options)?;
+    let mut output = String::new();
+    write_gap(&mut output, leading, false, !elements.is_empty(), options);
+    for (i, (element, span, gap)) in elements.iter().enumerate() {
+        let mut formatter = Formatter::new(options);
+        match element {
+            ScriptElement::Statement(statement) => formatter.statement(statement, 0),
+            ScriptElement::ShellCommand(command) => formatter.line(0, None, command.to_string()),
+        }
+        // Any comment not placed before an earlier line precedes the last one
+        if let Some((_, end)) = formatter.lines.last_mut() {
+            *end = Some(span.end);
+        }
+        let comments = if options.preserve_comments {
+            inner_comments(value, *span)
+        } else {
+            vec![]
+        };
+        output.push_str(&formatter.finish(&comments));
+        output.push(';');
+        write_gap(&mut output, gap, true, i + 1 < elements.len(), options);
+    }
+    Ok(output)
+}
+
+/// Writes the comments and line breaks found in the text between two script elements.
+fn write_gap(
+    output: &mut String,
+    gap: &str,
+    after_element: bool,
+    before_element: bool,
+    options: FormatOptions,
+) {
+    let mut at_line_start = !after_element;
+    let mut newlines = 0;
+    let mut rest = gap;
+    while let Some(chr) = rest.chars().next() {
+        let Some(comment_len) = comment_len(rest) else {
+            match chr {
+                '\n' => newlines += 1,
+                // A line holding the terminator is not blank
+                ';' => newlines = 0,
+                _ => {}
+            }
+            rest = &rest[chr.len_utf8()..];
+            continue;
+        };
+        let (comment, after_comment) = rest.split_at(comment_len);
+        rest = after_comment;
+        if !options.preserve_comments {
+            continue;
+        }
+        if after_element && newlines == 0 && !at_line_start {
+            // A comment on the same line as its statement stays there
+            output.push(' ');
+        } else {
+            if !at_line_start {
+                output.push('\n');
+            }
+            if newlines > 1 && !output.is_empty() {
+                output.push('\n');
+            }
+        }
+        output.push_str(comment.trim_end());
+        at_line_start = false;
+        newlines = 0;
+    }
+    if !at_line_start {
+        output.push('\n');
+    }
+    if before_element && newlines > 1 && !output.is_empty() {
+        output.push('\n');
+    }
+}
+
+/// The length of the comment at the start of `text`, excluding the line break that ends a `--` or `//` comment.
+fn comment_len(text: &str) -> Option<usize> {
+    if text.starts_with("--") || text.starts_with("//") {
+        Some(text.find('\n').unwrap_or(text.len()))
+    } else if text.starts_with("/*") {
+        Some(text.find("*/").map_or(text.len(), |end| end + 2))
+    } else {
+        None
+    }
+}
+
+/// Returns the comments within `span` of `source`, each paired with the offset of the token that follows it.
+fn inner_comments(source: &str, span: Span) -> Vec<(usize, &str)> {
+    let mut comments = vec![];
+    let mut offset = span.start;
+    while offset < span.end {
+        let rest = &source[offset..span.end];
+        let chr = rest.chars().next().unwrap();
+        offset += match chr {
+            '\'' | '"' => quoted_len(rest, chr),
+            '$' if rest.starts_with("$$") => rest[2..].find("$$").map_or(rest.len(), |end| end + 4),
+            _ => match comment_len(rest) {
+                Some(len) => {
+                    comments.push((offset, &rest[..len]));
+                    len
+                }
+                None => chr.len_utf8(),
+            },
+        };
+    }
+
+    // Consecutive comments all attach to the token after the last of them
+    let mut anchored = vec![];
+    let mut following: Option<(usize, usize)> = None;
+    for (start, comment) in comments.into_iter().rev() {
+        let rest = &source[start + comment.len()..];
+        let mut anchor = source.len() - rest.trim_start().len();
+        if let Some((following_start, following_anchor)) = following {
+            if following_start == anchor {
+                anchor = following_anchor;
+            }
+        }
+        following = Some((start, anchor));
+        anchored.push((anchor, comment.trim_end()));
+    }
+    anchored.reverse();
+    anchored
+}
+
+/// [`Display`] writes every keyword in upper case and quotes any identifier that is not lower case,
+/// so lowering the upper case words outside of literals and quoted identifiers changes only keywords.
+fn apply_keyword_case(text: &str, case: KeywordCase) -> String {
+    if case == KeywordCase::Upper {
+        return text.to_string();
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(chr) = rest.chars().next() {
+        let len = match chr {
+            '\'' | '"' => quoted_len(rest, chr),
+            '$' if rest.starts_with("$$") => rest[2..].find("$$").map_or(rest.len(), |end| end + 4),
+            chr if chr.is_ascii_alphanumeric() || chr == '_' || chr == '-' => {
+                let len = rest
+                    .find(|chr: char| !(chr.is_ascii_alphanumeric() || chr == '_' || chr == '-'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                // UUIDs, numbers and literals such as `NaN` are not entirely upper case letters
+                if word
+                    .chars()
+                    .all(|chr| chr.is_ascii_uppercase() || chr == '_')
+                {
+                    output.push_str(&word.to_ascii_lowercase());
+                    rest = &rest[len..];
+                    continue;
+                }
+                len
+            }
+            chr => chr.len_utf8(),
+        };
+        output.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    output
+}
+
+/// The length of the quoted text at the start of `text`, where a doubled quote is an escaped quote.
+fn quoted_len(text: &str, quote: char) -> usize {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, chr)) = chars.next() {
+        if chr == quote {
+            if chars.peek().map(|(_, next)| *next) == Some(quote) {
+                chars.next();
+            } else {
+                return i + 1;
+            }
+        }
+    }
+    text.len()
+}
+
+fn join<T: Display>(items: impl IntoIterator<Item = T>, separator: &str) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+struct Formatter {
+    options: FormatOptions,
+    /// Each line with the source offset of the end of the last node on it, when that is known.
+    lines: Vec<(String, Option<usize>)>,
+}
+
+impl Formatter {
+    fn new(options: FormatOptions) -> Self {
+        Formatter {
+            options,
+            lines: vec![],
+        }
+    }
+
+    fn line(&mut self, depth: usize, end: Option<usize>, text: impl Into<String>) {
+        let text = " ".repeat(depth * self.options.indent) + &text.into();
+        self.lines.push((text, end));
+    }
+
+    fn fits(&self, depth: usize, text: &str) -> bool {
+        depth * self.options.indent + text.chars().count() <= self.options.max_width
+    }
+
+    /// Appends to the last line written, used for the `,` and `;` that follow a nested element.
+    fn append(&mut self, text: &str) {
+        if let Some((line, _)) = self.lines.last_mut() {
+            line.push_str(text);
+        }
+    }
+
+    /// Joins the lines, writing each comment on its own line before the first line ending after the comment's anchor.
+    fn finish(self, comments: &[(usize, &str)]) -> String {
+        let text = self
+            .lines
+            .iter()
+            .map(|(line, _)| line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        // Changing the keyword case only changes ASCII letters, so every line keeps its offset
+        let text = apply_keyword_case(&text, self.options.keyword_case);
+        let mut output = String::with_capacity(text.len());
+        let mut comments = comments.iter().peekable();
+        let mut offset = 0;
+        for (line, end) in &self.lines {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            while let Some((_, comment)) =
+                comments.next_if(|(anchor, _)| end.is_some_and(|end| end > *anchor))
+            {
+                output.push_str(indent);
+                output.push_str(comment);
+                output.push('\n');
+            }
+            output.push_str(&text[offset..offset + line.len()]);
+            output.push('\n');
+            offset += line.len() + 1;
+        }
+        output.pop();
+        output
+    }
+
+    fn statement(&mut self, statement: &Statement, depth: usize) {
+        let single_line = statement.to_string();
+        let forced = match statement {
+            Statement::Select(select) => self.selectors_forced(select),
+            Statement::CreateTable(table) => {
+                self.options.align_with_options
+                    && (!table.clustering_order.is_empty()
+                        || table.compact_storage
+                        || !table.options.is_empty())
+            }
+            _ => false,
+        };
+        if !forced && self.fits(depth, &single_line) {
+            self.line(depth, None, single_line);
+            return;
+        }
+        match statement {
+            Statement::Select(select) => self.select(select, depth),
+            Statement::Insert(insert) => self.insert(insert, depth),
+            Statement::Update(update) => self.update(update, depth),
+            Statement::Delete(delete) => self.delete(delete, depth),
+            Statement::Batch(batch) => self.batch(batch, depth),
+            Statement::CreateTable(table) => self.create_table(table, depth),
+            Statement::CreateMaterializedView(view) => self.create_materialized_view(view, depth),
+            _ => self.line(depth, None, single_line),
+        }
+    }
+
+    /// Writes `single_line` unless it is `forced` onto several lines or does not fit, in which case `write` writes the lines.
+    fn nested(
+        &mut self,
+        depth: usize,
+        single_line: String,
+        forced: bool,
+        write: impl FnOnce(&mut Formatter),
+    ) {
+        let mut nested = Formatter::new(self.options);
+        write(&mut nested);
+        if !forced && self.fits(depth, &single_line) {
+            let end = nested.lines.iter().filter_map(|(_, end)| *end).max();
+            self.line(depth, end, single_line);
+        } else {
+            self.lines.extend(nested.lines);
+        }
+    }
+
+    fn selectors_forced(&self, select: &Select) -> bool {
+        self.options.one_column_per_line && select.select.len() > 1
+    }
+
+    fn select(&mut self, select: &Select, depth: usize) {
+        let mut head = "SELECT".to_string();
+        if select.json {
+            head.push_str(" JSON");
+        }
+        if select.distinct {
+            head.push_str(" DISTINCT");
+        }
+        let selectors = format!("{} {}", head, join(&select.select, ", "));
+        if self.selectors_forced(select) || !self.fits(depth, &selectors) {
+            self.line(depth, None, head);
+            self.list(&select.select, |selector| selector.span, depth + 1);
+        } else {
+            let end = last_end(&select.select, |selector| selector.span);
+            self.line(depth, end, selectors);
+        }
+        let from = format!("FROM {}", select.from);
+        self.line(depth, Some(select.from.span.end), from);
+        self.relations("WHERE", &select.where_, depth);
+        if !select.group_by.is_empty() {
+            let columns = join(&select.group_by, ", ");
+            let end = last_end(&select.group_by, |column| column.span);
+            self.line(depth, end, format!("GROUP BY {}", columns));
+        }
+        if !select.order_by.is_empty() {
+            let end = last_end(&select.order_by, |order| order.span);
+            let order_by = format!("ORDER BY {}", join(&select.order_by, ", "));
+            self.line(depth, end, order_by);
+        }
+        if let Some(limit) = &select.per_partition_limit {
+            self.line(depth, None, format!("PER PARTITION LIMIT {}", limit));
+        }
+        if let Some(limit) = &select.limit {
+            self.line(depth, None, format!("LIMIT {}", limit));
+        }
+        if select.allow_filtering {
+            self.line(depth, None, "ALLOW FILTERING");
+        }
+        self.using(&select.using, depth);
+    }
+
+    fn insert(&mut self, insert: &Insert, depth: usize) {
+        let columns = join(&insert.columns, ", ");
+        let end = last_end(&insert.columns, |column| column.span).unwrap_or(insert.table.span.end);
+        let head = format!("INSERT INTO {} ({})", insert.table, columns);
+        self.line(depth, Some(end), head);
+        let end = last_end(&insert.values, Expr::span);
+        self.line(
+            depth,
+            end,
+            format!("VALUES ({})", join(&insert.values, ", ")),
+        );
+        self.condition(&insert.condition, depth);
+        self.using(&insert.using, depth);
+    }
+
+    fn update(&mut self, update: &Update, depth: usize) {
+        let head = format!("UPDATE {}", update.table);
+        self.line(depth, Some(update.table.span.end), head);
+        self.using(&update.using, depth);
+        let set = format!("SET {}", join(&update.set, ", "));
+        if self.fits(depth, &set) {
+            let end = last_end(&update.set, |assignment| assignment.span);
+            self.line(depth, end, set);
+        } else {
+            self.line(depth, None, "SET");
+            self.list(&update.set, |assignment| assignment.span, depth + 1);
+        }
+        self.relations("WHERE", &update.where_, depth);
+        self.condition(&update.condition, depth);
+    }
+
+    fn delete(&mut self, delete: &Delete, depth: usize) {
+        let from = Some(delete.table.span.end);
+        if delete.columns.is_empty() {
+            self.line(depth, from, format!("DELETE FROM {}", delete.table));
+        } else {
+            let end = last_end(&delete.columns, Expr::span);
+            self.line(
+                depth,
+                end,
+                format!("DELETE {}", join(&delete.columns, ", ")),
+            );
+            self.line(depth, from, format!("FROM {}", delete.table));
+        }
+        self.using(&delete.using, depth);
+        self.relations("WHERE", &delete.where_, depth);
+        self.condition(&delete.condition, depth);
+    }
+
+    fn batch(&mut self, batch: &Batch, depth: usize) {
+        let mut head = match batch.batch_type {
+            BatchType::Logged => "BEGIN BATCH".to_string(),
+            BatchType::Unlogged => "BEGIN UNLOGGED BATCH".to_string(),
+            BatchType::Counter => "BEGIN COUNTER BATCH".to_string(),
+        };
+        if let Some(using) = &batch.using {
+            head = format!("{} {}", head, using);
+        }
+        let end = batch.using.as_ref().map(|using| using.span.end);
+        self.line(depth, end, head);
+        for statement in &batch.statements {
+            self.nested(
+                depth + 1,
+                statement.to_string(),
+                false,
+                |nested| match statement {
+                    BatchStatement::Insert(insert) => nested.insert(insert, depth + 1),
+                    BatchStatement::Update(update) => nested.update(update, depth + 1),
+                    BatchStatement::Delete(delete) => nested.delete(delete, depth + 1),
+                },
+            );
+            self.append(";");
+        }
+        self.line(depth, None, "APPLY BATCH");
+    }
+
+    fn create_table(&mut self, table: &CreateTable, depth: usize) {
+        let mut head = "CREATE TABLE".to_string();
+        if table.if_not_exists {
+            head.push_str(" IF NOT EXISTS");
+        }
+        let head = format!("{} {} (", head, table.name);
+        self.line(depth, Some(table.name.span.end), head);
+        for column in &table.columns {
+            self.line(
+                depth + 1,
+                Some(column.name.span.end),
+                format!("{},", column),
+            );
+        }
+        let primary_key = PrimaryKey(&table.partition_key, &table.clustering_key);
+        let end = last_end(&table.clustering_key, |column| column.span)
+            .or_else(|| last_end(&table.partition_key, |column| column.span));
+        self.line(depth + 1, end, primary_key.to_string());
+        self.line(depth, None, ")");
+        self.table_options(
+            ") WITH",
+            &table.clustering_order,
+            table.compact_storage,
+            &table.options,
+            depth,
+        );
+    }
+
+    fn create_materialized_view(&mut self, view: &CreateMaterializedView, depth: usize) {
+        let mut head = "CREATE MATERIALIZED VIEW".to_string();
+        if view.if_not_exists {
+            head.push_str(" IF NOT EXISTS");
+        }
+        let head = format!("{} {} AS", head, view.name);
+        self.line(depth, Some(view.name.span.end), head);
+        let forced = self.selectors_forced(&view.select);
+        self.nested(depth + 1, view.select.to_string(), forced, |nested| {
+            nested.select(&view.select, depth + 1)
+        });
+        let primary_key = PrimaryKey(&view.partition_key, &view.clustering_key);
+        let end = last_end(&view.clustering_key, |column| column.span)
+            .or_else(|| last_end(&view.partition_key, |column| column.span));
+        self.line(depth, end, primary_key.to_string());
+        self.table_options("WITH", &view.clustering_order, false, &view.options, depth);
+    }
+
+    /// Writes table options after `keyword`, which ends the last line when it is `) WITH`.
+    fn table_options(
+        &mut self,
+        keyword: &str,
+        clustering_order: &[OrderBy],
+        compact_storage: bool,
+        properties: &[Property],
+        depth: usize,
+    ) {
+        let mut options = vec![];
+        if !clustering_order.is_empty() {
+            let end = last_end(clustering_order, |order| order.span);
+            options.push((ClusteringOrder(clustering_order).to_string(), end));
+        }
+        if compact_storage {
+            options.push(("COMPACT STORAGE".to_string(), None));
+        }
+        if self.options.align_with_options {
+            let width = properties
+                .iter()
+                .map(|property| Identifier(&property.name).to_string().chars().count())
+                .max()
+                .unwrap_or(0);
+            options.extend(properties.iter().map(|property| {
+                let name = Identifier(&property.name).to_string();
+                let option = format!("{:width$} = {}", name, property.value, width = width);
+                (option, None)
+            }));
+        } else {
+            options.extend(
+                properties
+                    .iter()
+                    .map(|property| (property.to_string(), None)),
+            );
+        }
+        if options.is_empty() {
+            return;
+        }
+
+        let first_line = |this: &mut Self, end: Option<usize>, text: String| {
+            if keyword.starts_with(')') {
+                this.lines.pop();
+            }
+            this.line(depth, end, text);
+        };
+        let single_line = format!(
+            "{} {}",
+            keyword,
+            join(options.iter().map(|(option, _)| option), " AND ")
+        );
+        if !self.options.align_with_options && self.fits(depth, &single_line) {
+            let end = options.iter().filter_map(|(_, end)| *end).max();
+            first_line(self, end, single_line);
+            return;
+        }
+        let (first, end) = &options[0];
+        first_line(self, *end, format!("{} {}", keyword, first));
+        for (option, end) in &options[1..] {
+            if self.options.align_with_options {
+                let and = format!("{:>width$}", "AND", width = keyword.len());
+                self.line(depth, *end, format!("{} {}", and, option));
+            } else {
+                self.line(depth + 1, *end, format!("AND {}", option));
+            }
+        }
+    }
+
+    fn relations(&mut self, keyword: &str, relations: &[RelationElement], depth: usize) {
+        if relations.is_empty() {
+            return;
+        }
+        let single_line = format!("{} {}", keyword, join(relations, " AND "));
+        if self.fits(depth, &single_line) {
+            let end = last_end(relations, RelationElement::span);
+            self.line(depth, end, single_line);
+            return;
+        }
+        let end = Some(relations[0].span().end);
+        self.line(depth, end, format!("{} {}", keyword, relations[0]));
+        for relation in &relations[1..] {
+            let end = Some(relation.span().end);
+            self.line(depth + 1, end, format!("AND {}", relation));
+        }
+    }
+
+    fn condition(&mut self, condition: &Option<Condition>, depth: usize) {
+        match condition {
+            Some(Condition::Columns(relations)) => self.relations("IF", relations, depth),
+            Some(condition) => self.line(depth, None, condition.to_string()),
+            None => {}
+        }
+    }
+
+    fn using(&mut self, using: &Option<UsingClause>, depth: usize) {
+        if let Some(using) = using {
+            self.line(depth, Some(using.span.end), using.to_string());
+        }
+    }
+
+    /// Writes one item per line, separated by `,`.
+    fn list<T: Display>(&mut self, items: &[T], span: impl Fn(&T) -> Span, depth: usize) {
+        for (i, item) in items.iter().enumerate() {
+            let separator = if i + 1 < items.len() { "," } else { "" };
+            self.line(
+                depth,
+                Some(span(item).end),
+                format!("{}{}", item, separator),
+            );
+        }
+    }
+}
+
+/// The source offset the last of `items` ends at.
+fn last_end<T>(items: &[T], span: impl Fn(&T) -> Span) -> Option<usize> {
+    items.last().map(|item| span(item).end)
+}
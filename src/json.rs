@@ -0,0 +1,177 @@
+use crate::ast::*;
+
+impl Select {
+    /// Returns the keys of the JSON object Cassandra returns for each row of this `SELECT JSON` statement, in selector order.
+    ///
+    /// Returns `None` if the statement is not a `SELECT JSON` or if it selects `*`, as the keys then depend on the table schema.
+    pub fn json_keys(&self) -> Option<Vec<String>> {
+        if !self.json {
+            return None;
+        }
+
+        self.select
+            .iter()
+            .map(|element| {
+                let name = match &element.as_alias {
+                    Some(alias) => alias.value.clone(),
+                    None => selector_name(&element.expr, self.from.keyspace.as_deref())?,
+                };
+                Some(json_key(name))
+            })
+            .collect()
+    }
+}
+
+/// Cassandra wraps case sensitive names in double quotes so that the keys can be fed back into `INSERT JSON`.
+fn json_key(name: String) -> String {
+    if name.to_lowercase() == name {
+        name
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// The column name Cassandra gives a selector in the result set metadata.
+fn selector_name(expr: &Expr, keyspace: Option<&str>) -> Option<String> {
+    Some(match expr {
+        Expr::Name(name) => name.value.clone(),
+        Expr::Wildcard(_) => return None,
+        Expr::Constant(constant, _) => constant.to_string(),
+        Expr::BindMarker(BindMarker::Anonymous, _) => "?".to_string(),
+        Expr::BindMarker(BindMarker::Named(name), _) => format!(":{}", name),
+        Expr::Subscript(subscript) => format!(
+            "{}[{}]",
+            subscript.column,
+            selector_name(&subscript.key, keyspace)?
+        ),
+        Expr::FunctionCall(call) => {
+            let args = call
+                .args
+                .iter()
+                .map(|arg| match arg {
+                    Expr::Wildcard(_) => Some("*".to_string()),
+                    arg => selector_name(arg, keyspace),
+                })
+                .collect::<Option<Vec<_>>>()?
+                .join(", ");
+            let function = &call.function;
+            match function.keyspace.as_deref() {
+                // WRITETIME and TTL are selectors rather than functions so are never qualified
+                None if function.name == "writetime" || function.name == "ttl" => {
+                    format!("{}({})", function.name, args)
+                }
+                Some(keyspace) => format!("{}.{}({})", keyspace, function.name, args),
+                None if is_native_function(&function.name) => {
+                    format!("system.{}({})", function.name, args)
+                }
+                None => match keyspace {
+                    Some(keyspace) => format!("{}.{}({})", keyspace, function.name, args),
+                    None => format!("{}({})", function.name, args),
+                },
+            }
+        }
+        Expr::List(values, _) => format!("[{}]", selector_names(values, keyspace)?),
+        Expr::Set(values, _) => format!("{{{}}}", selector_names(values, keyspace)?),
+        Expr::Tuple(values, _) => format!("({})", selector_names(values, keyspace)?),
+        Expr::Map(entries, _) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|(key, value)| Some(format!(
+                    "{}: {}",
+                    selector_name(key, keyspace)?,
+                    selector_name(value, keyspace)?
+                )))
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        ),
+    })
+}
+
+fn selector_names(values: &[Expr], keyspace: Option<&str>) -> Option<String> {
+    Some(
+        values
+            .iter()
+            .map(|value| selector_name(value, keyspace))
+            .collect::<Option<Vec<_>>>()?
+            .join(", "),
+    )
+}
+
+/// Functions that live in the `system` keyspace and so are resolved without a keyspace.
+///
+/// This covers every native function up to Cassandra 5.0, including the snake case aliases 5.0 added for the older names.
+fn is_native_function(name: &str) -> bool {
+    const NATIVE_FUNCTIONS: &[&str] = &[
+        "token",
+        "uuid",
+        // Aggregates
+        "count",
+        "max",
+        "min",
+        "sum",
+        "avg",
+        // Time
+        "now",
+        "currenttimestamp",
+        "current_timestamp",
+        "currentdate",
+        "current_date",
+        "currenttime",
+        "current_time",
+        "currenttimeuuid",
+        "current_timeuuid",
+        "mintimeuuid",
+        "min_timeuuid",
+        "maxtimeuuid",
+        "max_timeuuid",
+        "dateof",
+        "unixtimestampof",
+        "todate",
+        "to_date",
+        "totimestamp",
+        "to_timestamp",
+        "tounixtimestamp",
+        "to_unix_timestamp",
+        "floor",
+        // JSON
+        "tojson",
+        "to_json",
+        "fromjson",
+        "from_json",
+        // Math
+        "abs",
+        "exp",
+        "log",
+        "log10",
+        "round",
+        // Collections
+        "map_keys",
+        "map_values",
+        "collection_count",
+        "collection_min",
+        "collection_max",
+        "collection_sum",
+        "collection_avg",
+        // Masking
+        "mask_null",
+        "mask_default",
+        "mask_replace",
+        "mask_inner",
+        "mask_outer",
+        "mask_hash",
+        // Vectors
+        "similarity_cosine",
+        "similarity_euclidean",
+        "similarity_dot_product",
+        // Formatting
+        "format_bytes",
+        "format_time",
+    ];
+    // Blob conversions exist for every type, as both `blobastype`/`typeasblob` and `blob_as_type`/`type_as_blob`
+    NATIVE_FUNCTIONS.contains(&name)
+        || name.starts_with("blobas")
+        || name.starts_with("blob_as_")
+        || name.ends_with("asblob")
+        || name.ends_with("_as_blob")
+}
@@ -1,27 +1,265 @@
 pub mod ast;
+mod display;
+mod error;
+mod fingerprint;
+mod format;
+mod json;
 pub(crate) mod parser;
+mod redact;
+mod resolve;
+pub mod visit;
+pub mod visit_mut;
 
 use std::str;
 
 use nom::branch::alt;
-use nom::combinator::map;
+use nom::bytes::complete::tag;
+use nom::combinator::{map, opt};
+use nom::sequence::tuple;
 use nom::IResult;
 
 use crate::ast::*;
+use crate::parser::batch::batch;
+use crate::parser::common::whitespace0;
+use crate::parser::delete::delete;
+use crate::parser::describe::describe;
+use crate::parser::function::{create_aggregate, create_function, drop_aggregate, drop_function};
+use crate::parser::index::{create_index, drop_index};
 use crate::parser::insert::insert;
-use crate::parser::select::select;
+use crate::parser::keyspace::{alter_keyspace, create_keyspace, drop_keyspace, use_keyspace};
+use crate::parser::permission::{grant_permission, list_permissions, revoke_permission};
+use crate::parser::role::{
+    alter_role, alter_user, create_role, create_user, drop_role, drop_user, grant_role, list_roles,
+    list_users, revoke_role,
+};
+use crate::parser::select::{clause, select, SelectClause};
+use crate::parser::shell::shell_command;
+use crate::parser::table::{alter_table, create_table, drop_table, truncate};
+use crate::parser::trigger::{create_trigger, drop_trigger};
+use crate::parser::udt::{alter_type, create_type, drop_type};
+use crate::parser::update::update;
+use crate::parser::view::{
+    alter_materialized_view, create_materialized_view, drop_materialized_view,
+};
+use crate::parser::Input;
 
+pub use crate::error::ParseError;
+pub use crate::fingerprint::fingerprint;
+pub use crate::format::{format_script, format_statement, FormatOptions, KeywordCase};
+pub use crate::redact::redact;
+pub use crate::resolve::{resolve_keyspaces, resolve_script_keyspaces};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Only accept the grammar Cassandra itself accepts.
+    /// SELECT clauses must appear in the order WHERE, GROUP BY, ORDER BY, PER PARTITION LIMIT, LIMIT, ALLOW FILTERING
+    /// and list separators (`,` between selectors and `AND` between relations) are required.
+    pub strict: bool,
+    /// Accept the commands cqlsh interprets itself, such as `CONSISTENCY QUORUM`.
+    /// Only takes effect in [`parse_script`].
+    pub cqlsh: bool,
+}
+
+/// Parses a `;` separated list of statements, panicking if the input is invalid.
 pub fn parse(value: &str) -> Vec<Statement> {
-    vec![sql_query(value.as_bytes()).unwrap().1]
+    parse_with(value, ParseOptions::default()).unwrap()
+}
+
+/// Parses a `;` separated list of statements.
+///
+/// cqlsh shell commands are only accepted by [`parse_script`], so `ParseOptions::cqlsh` is ignored.
+pub fn parse_with(value: &str, options: ParseOptions) -> Result<Vec<Statement>, ParseError> {
+    let options = ParseOptions {
+        cqlsh: false,
+        ..options
+    };
+    Ok(parse_script(value, options)?
+        .into_iter()
+        .filter_map(|element| match element {
+            ScriptElement::Statement(statement) => Some(statement),
+            ScriptElement::ShellCommand(_) => None,
+        })
+        .collect())
+}
+
+/// Parses a `;` separated list of statements, also returning the span of each statement.
+///
+/// Nodes within the statements record their own spans, see [`ast::Span`].
+pub fn parse_with_spans(
+    value: &str,
+    options: ParseOptions,
+) -> Result<Vec<(Statement, Span)>, ParseError> {
+    let options = ParseOptions {
+        cqlsh: false,
+        ..options
+    };
+    let (_, elements) = parse_script_with_gaps(value, options)?;
+    Ok(elements
+        .into_iter()
+        .filter_map(|(element, span, _)| match element {
+            ScriptElement::Statement(statement) => Some((statement, span)),
+            ScriptElement::ShellCommand(_) => None,
+        })
+        .collect())
+}
+
+/// Parses a `;` separated list of statements and, when `ParseOptions::cqlsh` is set, cqlsh shell commands.
+///
+/// Comments are skipped wherever whitespace is allowed.
+/// In cqlsh mode, as in cqlsh, a shell command may be terminated by a newline instead of `;`.
+pub fn parse_script(value: &str, options: ParseOptions) -> Result<Vec<ScriptElement>, ParseError> {
+    let (_, elements) = parse_script_with_gaps(value, options)?;
+    Ok(elements
+        .into_iter()
+        .map(|(element, _, _)| element)
+        .collect())
+}
+
+pub(crate) type ScriptWithGaps<'a> = (&'a str, Vec<(ScriptElement, Span, &'a str)>);
+
+/// Parses a script, also returning the text before the first element and the span of and text following each element.
+/// That text holds only whitespace, comments and `;` terminators.
+pub(crate) fn parse_script_with_gaps(
+    value: &str,
+    options: ParseOptions,
+) -> Result<ScriptWithGaps<'_>, ParseError> {
+    let input = Input::new(value.as_bytes());
+    let offset = |remaining: Input| remaining.location_offset();
+    let mut elements = vec![];
+    let (mut remaining, _) = whitespace0(input).unwrap();
+    let leading = &value[..offset(remaining)];
+    while !remaining.is_empty() {
+        let (rest, element) = script_element(options)(remaining).map_err(|err| match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => ParseError::Syntax {
+                offset: offset(err.input),
+            },
+            nom::Err::Incomplete(_) => ParseError::Syntax {
+                offset: value.len(),
+            },
+        })?;
+        let (after_element, (separator, terminator, _)) =
+            tuple((whitespace0, opt(tag(";")), whitespace0))(rest).unwrap();
+
+        let terminated = terminator.is_some()
+            || matches!(element, ScriptElement::ShellCommand(_)) && separator.contains(&b'\n');
+        if !terminated && !after_element.is_empty() {
+            return Err(match &element {
+                ScriptElement::Statement(statement) => {
+                    trailing_input_error(statement, rest, offset(after_element), options)
+                }
+                ScriptElement::ShellCommand(_) => ParseError::Syntax {
+                    offset: offset(after_element),
+                },
+            });
+        }
+
+        let span = Span {
+            start: offset(remaining),
+            end: offset(rest),
+        };
+        elements.push((element, span, &value[offset(rest)..offset(after_element)]));
+        remaining = after_element;
+    }
+    Ok((leading, elements))
+}
+
+fn trailing_input_error(
+    statement: &Statement,
+    rest: Input,
+    offset: usize,
+    options: ParseOptions,
+) -> ParseError {
+    if let (Statement::Select(select), Ok((_, clause))) = (statement, clause(options.strict)(rest))
+    {
+        let kind = clause.kind();
+        if kind.is_present(select) {
+            return ParseError::DuplicateClause {
+                clause: kind.name(),
+                offset,
+            };
+        }
+        if let Some(after) = SelectClause::last_present(select) {
+            return ParseError::ClauseOutOfOrder {
+                clause: kind.name(),
+                after: after.name(),
+                offset,
+            };
+        }
+    }
+    ParseError::Syntax { offset }
 }
 
 pub fn sql_query(i: &[u8]) -> IResult<&[u8], Statement> {
-    alt((
-        map(select, Statement::Select),
-        map(insert, Statement::Insert),
-    ))(i)
+    statement(ParseOptions::default())(Input::new(i))
+        .map(|(remaining, statement)| (*remaining.fragment(), statement))
+        .map_err(|err| err.map_input(|input| *input.fragment()))
+}
+
+fn script_element(options: ParseOptions) -> impl Fn(Input) -> IResult<Input, ScriptElement> {
+    move |i| {
+        if options.cqlsh {
+            match shell_command(i) {
+                Ok((remaining, command)) => {
+                    return Ok((remaining, ScriptElement::ShellCommand(command)))
+                }
+                Err(nom::Err::Error(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        map(statement(options), ScriptElement::Statement)(i)
+    }
 }
 
-// TODO: ooooooh because of all the backtracking we have to do, returning a String would be a performance nightmare.
-// Bytes is obviously a lot more efficient to clone than String but maybe we will see minor problems for even Byte due to reference counting?
-// We would have to implement and measure.
+fn statement(options: ParseOptions) -> impl Fn(Input) -> IResult<Input, Statement> {
+    move |i| {
+        alt((
+            alt((
+                map(select(options.strict), Statement::Select),
+                map(insert, Statement::Insert),
+                map(update, Statement::Update),
+                map(delete, Statement::Delete),
+                map(batch, Statement::Batch),
+                map(use_keyspace, Statement::Use),
+                map(describe, Statement::Describe),
+            )),
+            alt((
+                map(create_keyspace, Statement::CreateKeyspace),
+                map(alter_keyspace, Statement::AlterKeyspace),
+                map(drop_keyspace, Statement::DropKeyspace),
+                map(create_table, Statement::CreateTable),
+                map(alter_table, Statement::AlterTable),
+                map(drop_table, Statement::DropTable),
+                map(truncate, Statement::Truncate),
+                map(create_index, Statement::CreateIndex),
+                map(drop_index, Statement::DropIndex),
+                map(create_materialized_view, Statement::CreateMaterializedView),
+                map(alter_materialized_view, Statement::AlterMaterializedView),
+                map(drop_materialized_view, Statement::DropMaterializedView),
+                map(create_type, Statement::CreateType),
+                map(alter_type, Statement::AlterType),
+                map(drop_type, Statement::DropType),
+                map(create_function, Statement::CreateFunction),
+                map(drop_function, Statement::DropFunction),
+                map(create_aggregate, Statement::CreateAggregate),
+                map(drop_aggregate, Statement::DropAggregate),
+                map(create_trigger, Statement::CreateTrigger),
+                map(drop_trigger, Statement::DropTrigger),
+            )),
+            alt((
+                map(create_role, Statement::CreateRole),
+                map(alter_role, Statement::AlterRole),
+                map(drop_role, Statement::DropRole),
+                map(create_user, Statement::CreateUser),
+                map(alter_user, Statement::AlterUser),
+                map(drop_user, Statement::DropUser),
+                map(grant_permission, Statement::GrantPermission),
+                map(revoke_permission, Statement::RevokePermission),
+                map(grant_role, Statement::GrantRole),
+                map(revoke_role, Statement::RevokeRole),
+                map(list_roles, Statement::ListRoles),
+                map(list_users, |_| Statement::ListUsers),
+                map(list_permissions, Statement::ListPermissions),
+            )),
+        ))(i)
+    }
+}
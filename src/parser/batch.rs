@@ -0,0 +1,62 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::combinator::{cut, map, opt};
+use nom::multi::many1;
+use nom::sequence::{delimited, pair, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{using_clause, whitespace0, whitespace1};
+use crate::parser::delete::delete;
+use crate::parser::insert::insert;
+use crate::parser::update::update;
+use crate::parser::Input;
+
+pub fn batch(i: Input) -> IResult<Input, Batch> {
+    let (i, _) = tag_no_case("begin")(i)?;
+    let (remaining_input, (_, batch_type, _, using, statements, _, _, _, _)) = cut(tuple((
+        whitespace1,
+        batch_type,
+        tag_no_case("batch"),
+        opt(using_clause),
+        many1(delimited(
+            whitespace0,
+            batch_statement,
+            pair(whitespace0, opt(tag(";"))),
+        )),
+        whitespace0,
+        tag_no_case("apply"),
+        whitespace1,
+        tag_no_case("batch"),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        Batch {
+            batch_type,
+            using,
+            statements,
+        },
+    ))
+}
+
+fn batch_type(i: Input) -> IResult<Input, BatchType> {
+    map(
+        opt(terminated(
+            alt((
+                map(tag_no_case("unlogged"), |_| BatchType::Unlogged),
+                map(tag_no_case("counter"), |_| BatchType::Counter),
+            )),
+            whitespace1,
+        )),
+        |batch_type| batch_type.unwrap_or(BatchType::Logged),
+    )(i)
+}
+
+fn batch_statement(i: Input) -> IResult<Input, BatchStatement> {
+    alt((
+        map(insert, BatchStatement::Insert),
+        map(update, BatchStatement::Update),
+        map(delete, BatchStatement::Delete),
+    ))(i)
+}
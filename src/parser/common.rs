@@ -0,0 +1,627 @@
+use std::str;
+use std::str::FromStr;
+
+use nom::branch::alt;
+use nom::bytes::complete::{
+    is_not, tag, tag_no_case, take_until, take_while, take_while1, take_while_m_n,
+};
+use nom::character::complete::{alpha1, digit0, digit1, multispace1, not_line_ending};
+use nom::character::{is_alphanumeric, is_hex_digit};
+use nom::combinator::{map, map_opt, map_res, not, opt, recognize};
+use nom::multi::{fold_many0, many0, many1, separated_list0, separated_list1};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::Input;
+
+/// Runs `parser`, also returning the span of the input it consumed.
+pub fn spanned<'a, O>(
+    mut parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, (O, Span)> {
+    move |i| {
+        let (remaining, output) = parser(i)?;
+        let span = Span {
+            start: i.location_offset(),
+            end: remaining.location_offset(),
+        };
+        Ok((remaining, (output, span)))
+    }
+}
+
+pub fn relation(i: Input) -> IResult<Input, RelationElement> {
+    alt((
+        map(
+            spanned(tuple((
+                identifier,
+                whitespace1,
+                tag_no_case("is"),
+                whitespace1,
+                tag_no_case("not"),
+                whitespace1,
+                tag_no_case("null"),
+            ))),
+            |((column, _, _, _, _, _, _), span)| {
+                RelationElement::IsNotNull(RelationIsNotNull { column, span })
+            },
+        ),
+        map(
+            spanned(tuple((
+                identifier,
+                whitespace1,
+                tag_no_case("contains"),
+                whitespace1,
+                tag_no_case("key"),
+                whitespace1,
+                expr,
+            ))),
+            |((lhs, _, _, _, _, _, rhs), span)| {
+                RelationElement::ContainsKey(RelationContainsKey { lhs, rhs, span })
+            },
+        ),
+        map(
+            spanned(tuple((
+                identifier,
+                whitespace1,
+                tag_no_case("contains"),
+                whitespace1,
+                expr,
+            ))),
+            |((lhs, _, _, _, rhs), span)| {
+                RelationElement::Contains(RelationContains { lhs, rhs, span })
+            },
+        ),
+        map(
+            spanned(tuple((
+                expr,
+                whitespace1,
+                tag_no_case("in"),
+                whitespace0,
+                alt((
+                    map(parenthesized_list(expr), InValues::List),
+                    map(spanned(bind_marker), |(marker, span)| {
+                        InValues::BindMarker(marker, span)
+                    }),
+                )),
+            ))),
+            |((lhs, _, _, _, rhs), span)| RelationElement::In(RelationIn { lhs, rhs, span }),
+        ),
+        map(
+            spanned(tuple((expr, whitespace0, operator, whitespace0, expr))),
+            |((lhs, _, operator, _, rhs), span)| {
+                RelationElement::Comparison(RelationComparison {
+                    lhs,
+                    operator,
+                    rhs,
+                    span,
+                })
+            },
+        ),
+    ))(i)
+}
+
+pub fn relations(i: Input) -> IResult<Input, Vec<RelationElement>> {
+    separated_list1(ws_sep_and, relation)(i)
+}
+
+/// Parses `WHERE relation AND ...`, including its leading whitespace.
+pub fn where_clause(i: Input) -> IResult<Input, Vec<RelationElement>> {
+    preceded(
+        tuple((whitespace1, tag_no_case("where"), whitespace1)),
+        relations,
+    )(i)
+}
+
+/// Parses `IF EXISTS`, `IF NOT EXISTS` or `IF condition AND ...`, including its leading whitespace.
+pub fn condition(i: Input) -> IResult<Input, Condition> {
+    preceded(
+        pair(whitespace1, tag_no_case("if")),
+        alt((
+            map(
+                tuple((
+                    whitespace1,
+                    tag_no_case("not"),
+                    whitespace1,
+                    tag_no_case("exists"),
+                )),
+                |_| Condition::NotExists,
+            ),
+            map(pair(whitespace1, tag_no_case("exists")), |_| {
+                Condition::Exists
+            }),
+            map(preceded(whitespace1, relations), Condition::Columns),
+        )),
+    )(i)
+}
+
+/// Parses `USING TTL n AND TIMESTAMP n AND TIMEOUT d`, including its leading whitespace.
+/// Parses a USING clause, including its leading whitespace. As in Cassandra, each option may only be given once.
+pub fn using_clause(i: Input) -> IResult<Input, UsingClause> {
+    map_opt(
+        preceded(
+            whitespace1,
+            spanned(preceded(
+                pair(tag_no_case("using"), whitespace1),
+                separated_list1(ws_sep_and, using_element),
+            )),
+        ),
+        |(elements, span)| {
+            let mut using = UsingClause {
+                span,
+                ..UsingClause::default()
+            };
+            for (keyword, value) in elements {
+                let option = match keyword {
+                    UsingKeyword::Ttl => &mut using.ttl,
+                    UsingKeyword::Timestamp => &mut using.timestamp,
+                    UsingKeyword::Timeout => &mut using.timeout,
+                };
+                if option.replace(value).is_some() {
+                    return None;
+                }
+            }
+            Some(using)
+        },
+    )(i)
+}
+
+enum UsingKeyword {
+    Ttl,
+    Timestamp,
+    Timeout,
+}
+
+fn using_element(i: Input) -> IResult<Input, (UsingKeyword, UsingValue)> {
+    let integer = |i| {
+        alt((
+            map(integer_constant, UsingValue::Integer),
+            map(bind_marker, UsingValue::BindMarker),
+        ))(i)
+    };
+    alt((
+        map(
+            preceded(pair(tag_no_case("ttl"), whitespace1), integer),
+            |value| (UsingKeyword::Ttl, value),
+        ),
+        map(
+            preceded(pair(tag_no_case("timestamp"), whitespace1), integer),
+            |value| (UsingKeyword::Timestamp, value),
+        ),
+        map(
+            preceded(
+                pair(tag_no_case("timeout"), whitespace1),
+                alt((
+                    map(duration_literal, |duration: Input| {
+                        UsingValue::Duration(str::from_utf8(&duration).unwrap().to_string())
+                    }),
+                    map(bind_marker, UsingValue::BindMarker),
+                )),
+            ),
+            |value| (UsingKeyword::Timeout, value),
+        ),
+    ))(i)
+}
+
+/// Parses a duration literal such as `1h30m` or `500ms`
+pub fn duration_literal(i: Input) -> IResult<Input, Input> {
+    recognize(many1(pair(
+        digit1,
+        alt((
+            tag_no_case("ms"),
+            tag_no_case("mo"),
+            tag_no_case("us"),
+            tag("µs"),
+            tag_no_case("ns"),
+            tag_no_case("y"),
+            tag_no_case("w"),
+            tag_no_case("d"),
+            tag_no_case("h"),
+            tag_no_case("m"),
+            tag_no_case("s"),
+        )),
+    )))(i)
+}
+
+/// Parses `name = value AND ...`
+pub fn properties(i: Input) -> IResult<Input, Vec<Property>> {
+    separated_list1(ws_sep_and, property)(i)
+}
+
+pub fn property(i: Input) -> IResult<Input, Property> {
+    map(
+        separated_pair(
+            identifier,
+            tuple((whitespace0, tag("="), whitespace0)),
+            alt((
+                map(constant, PropertyValue::Constant),
+                map(property_map, PropertyValue::Map),
+                map(name, PropertyValue::Identifier),
+            )),
+        ),
+        |(name, value)| Property { name, value },
+    )(i)
+}
+
+pub fn property_map(i: Input) -> IResult<Input, Vec<(Constant, Constant)>> {
+    delimited(
+        pair(tag("{"), whitespace0),
+        separated_list0(
+            ws_sep_comma,
+            separated_pair(
+                constant,
+                tuple((whitespace0, tag(":"), whitespace0)),
+                constant,
+            ),
+        ),
+        pair(whitespace0, tag("}")),
+    )(i)
+}
+
+/// Parses `IF EXISTS`, including its leading whitespace.
+pub fn if_exists(i: Input) -> IResult<Input, bool> {
+    map(
+        opt(tuple((
+            whitespace1,
+            tag_no_case("if"),
+            whitespace1,
+            tag_no_case("exists"),
+        ))),
+        |x| x.is_some(),
+    )(i)
+}
+
+/// Parses `IF NOT EXISTS`, including its leading whitespace.
+pub fn if_not_exists(i: Input) -> IResult<Input, bool> {
+    map(
+        opt(tuple((
+            whitespace1,
+            tag_no_case("if"),
+            whitespace1,
+            tag_no_case("not"),
+            whitespace1,
+            tag_no_case("exists"),
+        ))),
+        |x| x.is_some(),
+    )(i)
+}
+
+pub fn operator(i: Input) -> IResult<Input, ComparisonOperator> {
+    alt((
+        map(tag("="), |_| ComparisonOperator::Equals),
+        map(tag("!="), |_| ComparisonOperator::NotEquals),
+        map(tag(">="), |_| ComparisonOperator::GreaterThanOrEqualTo),
+        map(tag(">"), |_| ComparisonOperator::GreaterThan),
+        map(tag("<="), |_| ComparisonOperator::LessThanOrEqualTo),
+        map(tag("<"), |_| ComparisonOperator::LessThan),
+    ))(i)
+}
+
+pub fn bind_marker(i: Input) -> IResult<Input, BindMarker> {
+    alt((
+        map(tag("?"), |_| BindMarker::Anonymous),
+        map(preceded(tag(":"), name), BindMarker::Named),
+    ))(i)
+}
+
+/// Parses an unsigned integer, failing if it does not fit in a `u64`
+pub fn unsigned_number(i: Input) -> IResult<Input, u64> {
+    map_res(digit1, |digits: Input| {
+        u64::from_str(str::from_utf8(&digits).unwrap())
+    })(i)
+}
+
+pub fn qualified_name(i: Input) -> IResult<Input, QualifiedName> {
+    map(
+        spanned(pair(identifier, opt(preceded(tag("."), identifier)))),
+        |((first, second), span)| match second {
+            Some(name) => QualifiedName {
+                keyspace: Some(first),
+                name,
+                span,
+            },
+            None => QualifiedName {
+                keyspace: None,
+                name: first,
+                span,
+            },
+        },
+    )(i)
+}
+
+/// Parses `( item, ... )`
+pub fn parenthesized_list<'a, O>(
+    item: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, Vec<O>> {
+    delimited(
+        pair(tag("("), whitespace0),
+        separated_list0(ws_sep_comma, item),
+        pair(whitespace0, tag(")")),
+    )
+}
+
+/// Parses `( item, ... )` with at least one item
+pub fn parenthesized_list1<'a, O>(
+    item: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, Vec<O>> {
+    delimited(
+        pair(tag("("), whitespace0),
+        separated_list1(ws_sep_comma, item),
+        pair(whitespace0, tag(")")),
+    )
+}
+
+pub fn expr(i: Input) -> IResult<Input, Expr> {
+    alt((
+        map(spanned(tag("*")), |(_, span)| Expr::Wildcard(span)),
+        map(spanned(constant), |(constant, span)| {
+            Expr::Constant(constant, span)
+        }),
+        map(spanned(bind_marker), |(marker, span)| {
+            Expr::BindMarker(marker, span)
+        }),
+        collection,
+        map(function_call, Expr::FunctionCall),
+        map(subscript, Expr::Subscript),
+        map(identifier, Expr::Name),
+    ))(i)
+}
+
+/// Parses `function(arg, ...)` or `keyspace.function(arg, ...)`
+pub fn function_call(i: Input) -> IResult<Input, FunctionCall> {
+    map(
+        spanned(pair(qualified_name, parenthesized_list(expr))),
+        |((function, args), span)| FunctionCall {
+            function,
+            args,
+            span,
+        },
+    )(i)
+}
+
+/// Parses a collection literal. `{}` is an empty set, which Cassandra also accepts as an empty map.
+pub fn collection(i: Input) -> IResult<Input, Expr> {
+    alt((
+        map(
+            spanned(delimited(
+                pair(tag("["), whitespace0),
+                separated_list0(ws_sep_comma, expr),
+                pair(whitespace0, tag("]")),
+            )),
+            |(values, span)| Expr::List(values, span),
+        ),
+        map(
+            spanned(delimited(
+                pair(tag("{"), whitespace0),
+                separated_list1(
+                    ws_sep_comma,
+                    separated_pair(expr, tuple((whitespace0, tag(":"), whitespace0)), expr),
+                ),
+                pair(whitespace0, tag("}")),
+            )),
+            |(entries, span)| Expr::Map(entries, span),
+        ),
+        map(
+            spanned(delimited(
+                pair(tag("{"), whitespace0),
+                separated_list0(ws_sep_comma, expr),
+                pair(whitespace0, tag("}")),
+            )),
+            |(values, span)| Expr::Set(values, span),
+        ),
+        map(spanned(parenthesized_list(expr)), |(values, span)| {
+            Expr::Tuple(values, span)
+        }),
+    ))(i)
+}
+
+/// Parses `column[key]`
+pub fn subscript(i: Input) -> IResult<Input, Subscript> {
+    map(
+        spanned(pair(
+            identifier,
+            delimited(
+                pair(tag("["), whitespace0),
+                expr,
+                pair(whitespace0, tag("]")),
+            ),
+        )),
+        |((column, key), span)| Subscript {
+            column,
+            key: Box::new(key),
+            span,
+        },
+    )(i)
+}
+
+pub fn constant(i: Input) -> IResult<Input, Constant> {
+    alt((
+        map(uuid_constant, Constant::UUID),
+        map(hex_constant, Constant::Hex),
+        map(float_constant, Constant::Float),
+        map(integer_constant, Constant::Decimal),
+        map(string_constant, Constant::String),
+        map(code_block_constant, Constant::CodeBlock),
+        map(bool_constant, Constant::Bool),
+        map(keyword("null"), |_| Constant::Null),
+    ))(i)
+}
+
+pub fn uuid_constant(i: Input) -> IResult<Input, String> {
+    let hex = |length| take_while_m_n(length, length, is_hex_digit);
+    map(
+        terminated(
+            recognize(tuple((
+                hex(8),
+                tag("-"),
+                hex(4),
+                tag("-"),
+                hex(4),
+                tag("-"),
+                hex(4),
+                tag("-"),
+                hex(12),
+            ))),
+            not(take_while1(is_identifier)),
+        ),
+        |uuid: Input| str::from_utf8(&uuid).unwrap().to_string(),
+    )(i)
+}
+
+/// Parses a blob literal such as `0xcafe`
+pub fn hex_constant(i: Input) -> IResult<Input, Vec<u8>> {
+    map_opt(
+        preceded(tag_no_case("0x"), take_while(is_hex_digit)),
+        |digits: Input| {
+            if !digits.len().is_multiple_of(2) {
+                return None;
+            }
+            digits
+                .chunks(2)
+                .map(|pair| u8::from_str_radix(str::from_utf8(pair).ok()?, 16).ok())
+                .collect()
+        },
+    )(i)
+}
+
+/// Parses a signed integer, failing if it does not fit in an `i64`.
+/// The sign is parsed along with the digits so `-9223372036854775808` is accepted.
+pub fn integer_constant(i: Input) -> IResult<Input, i64> {
+    map_res(recognize(pair(opt(tag("-")), digit1)), |bytes: Input| {
+        i64::from_str(str::from_utf8(&bytes).unwrap())
+    })(i)
+}
+
+/// Parses a float, including `NaN`, `Infinity` and `-Infinity`
+pub fn float_constant(i: Input) -> IResult<Input, f64> {
+    let exponent = |i| {
+        recognize(tuple((
+            tag_no_case("e"),
+            opt(alt((tag("+"), tag("-")))),
+            digit1,
+        )))(i)
+    };
+    alt((
+        map(
+            recognize(tuple((
+                opt(tag("-")),
+                digit1,
+                alt((
+                    recognize(tuple((tag("."), digit0, opt(exponent)))),
+                    exponent,
+                )),
+            ))),
+            |bytes: Input| f64::from_str(str::from_utf8(&bytes).unwrap()).unwrap(),
+        ),
+        map(keyword("nan"), |_| f64::NAN),
+        map(keyword("infinity"), |_| f64::INFINITY),
+        map(preceded(tag("-"), keyword("infinity")), |_| {
+            f64::NEG_INFINITY
+        }),
+    ))(i)
+}
+
+pub fn string_constant(i: Input) -> IResult<Input, String> {
+    map(raw_string_quoted, |bytes| String::from_utf8(bytes).unwrap())(i)
+}
+
+/// Parses `$$...$$`, returning the contents verbatim
+pub fn code_block_constant(i: Input) -> IResult<Input, String> {
+    map(
+        delimited(tag("$$"), take_until("$$"), tag("$$")),
+        |bytes: Input| str::from_utf8(&bytes).unwrap().to_string(),
+    )(i)
+}
+
+fn raw_string_quoted(i: Input) -> IResult<Input, Vec<u8>> {
+    delimited(
+        tag("'"),
+        fold_many0(
+            alt((
+                map(is_not("'"), |bytes: Input| *bytes.fragment()),
+                map(tag("''"), |_| &b"'"[..]),
+            )),
+            Vec::new,
+            |mut acc: Vec<u8>, bytes: &[u8]| {
+                acc.extend(bytes);
+                acc
+            },
+        ),
+        tag("'"),
+    )(i)
+}
+
+pub fn bool_constant(i: Input) -> IResult<Input, bool> {
+    alt((
+        map(keyword("true"), |_| true),
+        map(keyword("false"), |_| false),
+    ))(i)
+}
+
+/// Parses any amount of whitespace, `-- comments`, `// comments` and `/* comments */`
+pub fn whitespace0(i: Input) -> IResult<Input, Input> {
+    recognize(many0(whitespace_or_comment))(i)
+}
+
+/// Parses at least one whitespace character or comment, and any that follow
+pub fn whitespace1(i: Input) -> IResult<Input, Input> {
+    recognize(many1(whitespace_or_comment))(i)
+}
+
+fn whitespace_or_comment(i: Input) -> IResult<Input, Input> {
+    alt((
+        multispace1,
+        recognize(pair(alt((tag("--"), tag("//"))), not_line_ending)),
+        recognize(delimited(tag("/*"), take_until("*/"), tag("*/"))),
+    ))(i)
+}
+
+pub(crate) fn ws_sep_comma(i: Input) -> IResult<Input, Input> {
+    delimited(whitespace0, tag(","), whitespace0)(i)
+}
+
+pub(crate) fn ws_sep_and(i: Input) -> IResult<Input, Input> {
+    delimited(whitespace1, tag_no_case("and"), whitespace1)(i)
+}
+
+/// Parses an identifier, see [`name`].
+pub fn identifier(i: Input) -> IResult<Input, Identifier> {
+    map(spanned(name), |(value, span)| Identifier { value, span })(i)
+}
+
+/// Parses an identifier without recording its span, for names that are not identifiers such as bind markers and property values.
+/// Unquoted identifiers start with a letter and are case insensitive so are lowercased, quoted identifiers are returned verbatim.
+pub fn name(i: Input) -> IResult<Input, String> {
+    alt((
+        map(
+            recognize(pair(alpha1, take_while(is_identifier))),
+            |name: Input| str::from_utf8(&name).unwrap().to_lowercase(),
+        ),
+        map(
+            delimited(
+                tag("\""),
+                fold_many0(
+                    alt((
+                        map(is_not("\""), |bytes: Input| *bytes.fragment()),
+                        map(tag("\"\""), |_| &b"\""[..]),
+                    )),
+                    Vec::new,
+                    |mut acc: Vec<u8>, bytes: &[u8]| {
+                        acc.extend(bytes);
+                        acc
+                    },
+                ),
+                tag("\""),
+            ),
+            |bytes| String::from_utf8(bytes).unwrap(),
+        ),
+    ))(i)
+}
+
+/// Parses `keyword`, failing if it is only the prefix of an identifier
+pub fn keyword(keyword: &'static str) -> impl Fn(Input) -> IResult<Input, Input> {
+    move |i| terminated(tag_no_case(keyword), not(take_while1(is_identifier)))(i)
+}
+
+pub fn is_identifier(chr: u8) -> bool {
+    is_alphanumeric(chr) || chr == b'_'
+}
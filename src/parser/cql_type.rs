@@ -0,0 +1,87 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::combinator::map;
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, pair, preceded, separated_pair, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{qualified_name, string_constant, whitespace0, ws_sep_comma};
+use crate::parser::Input;
+
+pub fn cql_type(i: Input) -> IResult<Input, CqlType> {
+    alt((
+        map(
+            preceded(tag_no_case("list"), angle_brackets(cql_type)),
+            |inner| CqlType::List(Box::new(inner)),
+        ),
+        map(
+            preceded(tag_no_case("set"), angle_brackets(cql_type)),
+            |inner| CqlType::Set(Box::new(inner)),
+        ),
+        map(
+            preceded(
+                tag_no_case("map"),
+                angle_brackets(separated_pair(cql_type, ws_sep_comma, cql_type)),
+            ),
+            |(key, value)| CqlType::Map(Box::new(key), Box::new(value)),
+        ),
+        map(
+            preceded(
+                tag_no_case("tuple"),
+                angle_brackets(separated_list1(ws_sep_comma, cql_type)),
+            ),
+            CqlType::Tuple,
+        ),
+        map(
+            preceded(tag_no_case("frozen"), angle_brackets(cql_type)),
+            |inner| CqlType::Frozen(Box::new(inner)),
+        ),
+        map(string_constant, CqlType::Custom),
+        map(qualified_name, |name| match native_type(&name) {
+            Some(native) => CqlType::Native(native),
+            None => CqlType::UserDefined(name),
+        }),
+    ))(i)
+}
+
+/// Parses `< inner >`
+fn angle_brackets<'a, O>(
+    inner: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, O> {
+    delimited(
+        tuple((whitespace0, tag("<"), whitespace0)),
+        inner,
+        pair(whitespace0, tag(">")),
+    )
+}
+
+fn native_type(name: &QualifiedName) -> Option<NativeType> {
+    if name.keyspace.is_some() {
+        return None;
+    }
+    Some(match name.name.as_str() {
+        "ascii" => NativeType::Ascii,
+        "bigint" => NativeType::Bigint,
+        "blob" => NativeType::Blob,
+        "boolean" => NativeType::Boolean,
+        "counter" => NativeType::Counter,
+        "date" => NativeType::Date,
+        "decimal" => NativeType::Decimal,
+        "double" => NativeType::Double,
+        "duration" => NativeType::Duration,
+        "float" => NativeType::Float,
+        "inet" => NativeType::Inet,
+        "int" => NativeType::Int,
+        "smallint" => NativeType::Smallint,
+        "text" => NativeType::Text,
+        "time" => NativeType::Time,
+        "timestamp" => NativeType::Timestamp,
+        "timeuuid" => NativeType::Timeuuid,
+        "tinyint" => NativeType::Tinyint,
+        "uuid" => NativeType::Uuid,
+        "varchar" => NativeType::Varchar,
+        "varint" => NativeType::Varint,
+        _ => return None,
+    })
+}
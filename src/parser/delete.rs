@@ -0,0 +1,49 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, opt};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    condition, identifier, qualified_name, subscript, using_clause, where_clause, whitespace1,
+    ws_sep_comma,
+};
+use crate::parser::Input;
+
+pub fn delete(i: Input) -> IResult<Input, Delete> {
+    let (i, _) = tag_no_case("delete")(i)?;
+    let (remaining_input, (_, (columns, table), using, where_, condition)) = cut(tuple((
+        whitespace1,
+        alt((
+            map(from, |table| (vec![], table)),
+            pair(
+                terminated(separated_list1(ws_sep_comma, column), whitespace1),
+                from,
+            ),
+        )),
+        opt(using_clause),
+        where_clause,
+        opt(condition),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        Delete {
+            columns,
+            table,
+            using,
+            where_,
+            condition,
+        },
+    ))
+}
+
+fn from(i: Input) -> IResult<Input, QualifiedName> {
+    preceded(pair(tag_no_case("from"), whitespace1), qualified_name)(i)
+}
+
+fn column(i: Input) -> IResult<Input, Expr> {
+    alt((map(subscript, Expr::Subscript), map(identifier, Expr::Name)))(i)
+}
@@ -0,0 +1,84 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, not, opt};
+use nom::sequence::{pair, preceded, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{identifier, keyword, qualified_name, whitespace1};
+use crate::parser::Input;
+
+pub fn describe(i: Input) -> IResult<Input, Describe> {
+    let (i, _) = alt((keyword("describe"), keyword("desc")))(i)?;
+    let (remaining_input, (_, target, with_internals)) = cut(tuple((
+        whitespace1,
+        describe_target,
+        map(
+            opt(tuple((
+                whitespace1,
+                tag_no_case("with"),
+                whitespace1,
+                tag_no_case("internals"),
+            ))),
+            |internals| internals.is_some(),
+        ),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        Describe {
+            target,
+            with_internals,
+        },
+    ))
+}
+
+fn describe_target(i: Input) -> IResult<Input, DescribeTarget> {
+    let named = |name| preceded(pair(keyword(name), whitespace1), qualified_name);
+    alt((
+        map(keyword("cluster"), |_| DescribeTarget::Cluster),
+        map(
+            pair(opt(pair(keyword("full"), whitespace1)), keyword("schema")),
+            |(full, _)| DescribeTarget::Schema {
+                full: full.is_some(),
+            },
+        ),
+        map(keyword("keyspaces"), |_| DescribeTarget::Keyspaces),
+        map(
+            tuple((
+                opt(pair(keyword("only"), whitespace1)),
+                keyword("keyspace"),
+                opt(preceded(
+                    pair(whitespace1, not(keyword("with"))),
+                    identifier,
+                )),
+            )),
+            |(only, _, name)| DescribeTarget::Keyspace {
+                only: only.is_some(),
+                name,
+            },
+        ),
+        map(keyword("tables"), |_| DescribeTarget::Tables),
+        map(named("table"), DescribeTarget::Table),
+        map(named("index"), DescribeTarget::Index),
+        map(
+            preceded(
+                tuple((
+                    keyword("materialized"),
+                    whitespace1,
+                    keyword("view"),
+                    whitespace1,
+                )),
+                qualified_name,
+            ),
+            DescribeTarget::MaterializedView,
+        ),
+        map(keyword("types"), |_| DescribeTarget::Types),
+        map(named("type"), DescribeTarget::Type),
+        map(keyword("functions"), |_| DescribeTarget::Functions),
+        map(named("function"), DescribeTarget::Function),
+        map(keyword("aggregates"), |_| DescribeTarget::Aggregates),
+        map(named("aggregate"), DescribeTarget::Aggregate),
+        map(qualified_name, DescribeTarget::Name),
+    ))(i)
+}
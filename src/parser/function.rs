@@ -0,0 +1,203 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, opt};
+use nom::sequence::{preceded, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    code_block_constant, expr, identifier, if_exists, if_not_exists, name, parenthesized_list,
+    qualified_name, string_constant, whitespace0, whitespace1,
+};
+use crate::parser::cql_type::cql_type;
+use crate::parser::Input;
+
+pub fn create_function(i: Input) -> IResult<Input, CreateFunction> {
+    let (i, or_replace) = create_or_replace("function")(i)?;
+    let (
+        remaining_input,
+        (
+            if_not_exists,
+            _,
+            name,
+            _,
+            arguments,
+            _,
+            called_on_null_input,
+            _,
+            return_type,
+            _,
+            language,
+            _,
+            body,
+        ),
+    ) = cut(tuple((
+        if_not_exists,
+        whitespace1,
+        qualified_name,
+        whitespace0,
+        parenthesized_list(function_argument),
+        whitespace1,
+        on_null_input,
+        tuple((whitespace1, tag_no_case("returns"), whitespace1)),
+        cql_type,
+        tuple((whitespace1, tag_no_case("language"), whitespace1)),
+        name,
+        tuple((whitespace1, tag_no_case("as"), whitespace1)),
+        alt((
+            map(code_block_constant, Constant::CodeBlock),
+            map(string_constant, Constant::String),
+        )),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        CreateFunction {
+            or_replace,
+            if_not_exists,
+            name,
+            arguments,
+            called_on_null_input,
+            return_type,
+            language,
+            body,
+        },
+    ))
+}
+
+pub fn drop_function(i: Input) -> IResult<Input, DropFunction> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("function")))(i)?;
+    let (remaining_input, (if_exists, _, name, argument_types)) = cut(tuple((
+        if_exists,
+        whitespace1,
+        qualified_name,
+        argument_types,
+    )))(i)?;
+    Ok((
+        remaining_input,
+        DropFunction {
+            if_exists,
+            name,
+            argument_types,
+        },
+    ))
+}
+
+pub fn create_aggregate(i: Input) -> IResult<Input, CreateAggregate> {
+    let (i, or_replace) = create_or_replace("aggregate")(i)?;
+    let (
+        remaining_input,
+        (
+            if_not_exists,
+            _,
+            name,
+            _,
+            argument_types,
+            _,
+            state_function,
+            _,
+            state_type,
+            final_function,
+            initial_condition,
+        ),
+    ) = cut(tuple((
+        if_not_exists,
+        whitespace1,
+        qualified_name,
+        whitespace0,
+        parenthesized_list(cql_type),
+        tuple((whitespace1, tag_no_case("sfunc"), whitespace1)),
+        identifier,
+        tuple((whitespace1, tag_no_case("stype"), whitespace1)),
+        cql_type,
+        opt(preceded(
+            tuple((whitespace1, tag_no_case("finalfunc"), whitespace1)),
+            identifier,
+        )),
+        opt(preceded(
+            tuple((whitespace1, tag_no_case("initcond"), whitespace1)),
+            expr,
+        )),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        CreateAggregate {
+            or_replace,
+            if_not_exists,
+            name,
+            argument_types,
+            state_function,
+            state_type,
+            final_function,
+            initial_condition,
+        },
+    ))
+}
+
+pub fn drop_aggregate(i: Input) -> IResult<Input, DropAggregate> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("aggregate")))(i)?;
+    let (remaining_input, (if_exists, _, name, argument_types)) = cut(tuple((
+        if_exists,
+        whitespace1,
+        qualified_name,
+        argument_types,
+    )))(i)?;
+    Ok((
+        remaining_input,
+        DropAggregate {
+            if_exists,
+            name,
+            argument_types,
+        },
+    ))
+}
+
+/// Parses `CREATE [OR REPLACE] keyword`, returning whether `OR REPLACE` was present
+fn create_or_replace(keyword: &'static str) -> impl Fn(Input) -> IResult<Input, bool> {
+    move |i| {
+        let (i, (_, _, or_replace, _)) = tuple((
+            tag_no_case("create"),
+            whitespace1,
+            opt(terminated(
+                tuple((tag_no_case("or"), whitespace1, tag_no_case("replace"))),
+                whitespace1,
+            )),
+            tag_no_case(keyword),
+        ))(i)?;
+        Ok((i, or_replace.is_some()))
+    }
+}
+
+fn function_argument(i: Input) -> IResult<Input, FunctionArgument> {
+    map(
+        tuple((identifier, whitespace1, cql_type)),
+        |(name, _, data_type)| FunctionArgument { name, data_type },
+    )(i)
+}
+
+/// Parses `CALLED ON NULL INPUT` or `RETURNS NULL ON NULL INPUT`, returning true for the former
+fn on_null_input(i: Input) -> IResult<Input, bool> {
+    terminated(
+        alt((
+            map(tag_no_case("called"), |_| true),
+            map(
+                tuple((tag_no_case("returns"), whitespace1, tag_no_case("null"))),
+                |_| false,
+            ),
+        )),
+        tuple((
+            whitespace1,
+            tag_no_case("on"),
+            whitespace1,
+            tag_no_case("null"),
+            whitespace1,
+            tag_no_case("input"),
+        )),
+    )(i)
+}
+
+/// Parses the optional `(type, ...)` selecting an overload in `DROP FUNCTION` and `DROP AGGREGATE`
+fn argument_types(i: Input) -> IResult<Input, Option<Vec<CqlType>>> {
+    opt(preceded(whitespace0, parenthesized_list(cql_type)))(i)
+}
@@ -0,0 +1,91 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::combinator::{cut, map, map_opt, opt};
+use nom::sequence::{delimited, pair, preceded, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    identifier, if_exists, if_not_exists, parenthesized_list, properties, qualified_name,
+    string_constant, whitespace0, whitespace1,
+};
+use crate::parser::Input;
+
+pub fn create_index(i: Input) -> IResult<Input, CreateIndex> {
+    let (i, (_, _, custom, _)) = tuple((
+        tag_no_case("create"),
+        whitespace1,
+        opt(terminated(tag_no_case("custom"), whitespace1)),
+        tag_no_case("index"),
+    ))(i)?;
+    let custom = custom.is_some();
+    cut(map_opt(
+        tuple((
+            if_not_exists,
+            alt((
+                map(
+                    tuple((whitespace1, identifier, whitespace1, tag_no_case("on"))),
+                    |(_, name, _, _)| Some(name),
+                ),
+                map(pair(whitespace1, tag_no_case("on")), |_| None),
+            )),
+            whitespace1,
+            qualified_name,
+            whitespace0,
+            parenthesized_list(index_target),
+            opt(preceded(
+                tuple((whitespace1, tag_no_case("using"), whitespace1)),
+                string_constant,
+            )),
+            opt(preceded(
+                tuple((whitespace1, tag_no_case("with"), whitespace1)),
+                properties,
+            )),
+        )),
+        move |(if_not_exists, name, _, table, _, targets, using, properties)| {
+            let mut options = vec![];
+            for property in properties.unwrap_or_default() {
+                match (property.name.as_str(), property.value) {
+                    ("options", PropertyValue::Map(map)) => options = map,
+                    _ => return None,
+                }
+            }
+            Some(CreateIndex {
+                custom,
+                if_not_exists,
+                name,
+                table,
+                targets,
+                using,
+                options,
+            })
+        },
+    ))(i)
+}
+
+fn index_target(i: Input) -> IResult<Input, IndexTarget> {
+    let facet = |keyword: &'static str| {
+        preceded(
+            pair(tag_no_case(keyword), whitespace0),
+            delimited(
+                pair(tag("("), whitespace0),
+                identifier,
+                pair(whitespace0, tag(")")),
+            ),
+        )
+    };
+    alt((
+        map(facet("keys"), IndexTarget::Keys),
+        map(facet("values"), IndexTarget::Values),
+        map(facet("entries"), IndexTarget::Entries),
+        map(facet("full"), IndexTarget::Full),
+        map(identifier, IndexTarget::Column),
+    ))(i)
+}
+
+pub fn drop_index(i: Input) -> IResult<Input, DropIndex> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("index")))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, qualified_name)))(i)?;
+    Ok((remaining_input, DropIndex { if_exists, name }))
+}
@@ -1,10 +1,54 @@
 use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, opt};
 use nom::sequence::tuple;
 use nom::IResult;
 
 use crate::ast::*;
+use crate::parser::common::{
+    expr, identifier, parenthesized_list, qualified_name, using_clause, whitespace0, whitespace1,
+};
+use crate::parser::Input;
 
-pub fn insert(i: &[u8]) -> IResult<&[u8], Insert> {
-    let (remaining_input, _) = tuple((tag_no_case("insert"),))(i)?;
-    Ok((remaining_input, Insert {}))
+pub fn insert(i: Input) -> IResult<Input, Insert> {
+    let (i, _) = tag_no_case("insert")(i)?;
+    let (remaining_input, (_, _, _, table, _, columns, _, _, _, values, condition, using)) =
+        cut(tuple((
+            whitespace1,
+            tag_no_case("into"),
+            whitespace1,
+            qualified_name,
+            whitespace0,
+            parenthesized_list(identifier),
+            whitespace0,
+            tag_no_case("values"),
+            whitespace0,
+            parenthesized_list(expr),
+            opt(if_not_exists),
+            opt(using_clause),
+        )))(i)?;
+
+    Ok((
+        remaining_input,
+        Insert {
+            table,
+            columns,
+            values,
+            condition,
+            using,
+        },
+    ))
+}
+
+fn if_not_exists(i: Input) -> IResult<Input, Condition> {
+    map(
+        tuple((
+            whitespace1,
+            tag_no_case("if"),
+            whitespace1,
+            tag_no_case("not"),
+            whitespace1,
+            tag_no_case("exists"),
+        )),
+        |_| Condition::NotExists,
+    )(i)
 }
@@ -0,0 +1,136 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map_opt, opt};
+use nom::sequence::{preceded, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{identifier, if_exists, if_not_exists, properties, whitespace1};
+use crate::parser::Input;
+
+pub fn create_keyspace(i: Input) -> IResult<Input, CreateKeyspace> {
+    let (i, _) = tuple((tag_no_case("create"), whitespace1, keyspace_keyword))(i)?;
+    cut(map_opt(
+        tuple((if_not_exists, whitespace1, identifier, with_options)),
+        |(if_not_exists, _, name, (replication, durable_writes))| {
+            Some(CreateKeyspace {
+                if_not_exists,
+                name,
+                replication: replication?,
+                durable_writes,
+            })
+        },
+    ))(i)
+}
+
+pub fn alter_keyspace(i: Input) -> IResult<Input, AlterKeyspace> {
+    let (i, _) = tuple((tag_no_case("alter"), whitespace1, keyspace_keyword))(i)?;
+    cut(map_opt(
+        tuple((if_exists, whitespace1, identifier, with_options)),
+        |(if_exists, _, name, (replication, durable_writes))| {
+            if replication.is_none() && durable_writes.is_none() {
+                return None;
+            }
+            Some(AlterKeyspace {
+                if_exists,
+                name,
+                replication,
+                durable_writes,
+            })
+        },
+    ))(i)
+}
+
+pub fn drop_keyspace(i: Input) -> IResult<Input, DropKeyspace> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, keyspace_keyword))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, identifier)))(i)?;
+    Ok((remaining_input, DropKeyspace { if_exists, name }))
+}
+
+pub fn use_keyspace(i: Input) -> IResult<Input, Identifier> {
+    let (i, _) = tag_no_case("use")(i)?;
+    cut(preceded(whitespace1, identifier))(i)
+}
+
+fn keyspace_keyword(i: Input) -> IResult<Input, Input> {
+    alt((tag_no_case("keyspace"), tag_no_case("schema")))(i)
+}
+
+/// Parses `WITH replication = {...} AND durable_writes = bool`, failing on any other option.
+fn with_options(i: Input) -> IResult<Input, (Option<ReplicationStrategy>, Option<bool>)> {
+    map_opt(
+        opt(preceded(
+            tuple((whitespace1, tag_no_case("with"), whitespace1)),
+            properties,
+        )),
+        |properties| {
+            let mut replication = None;
+            let mut durable_writes = None;
+            for property in properties.unwrap_or_default() {
+                match (property.name.as_str(), property.value) {
+                    ("replication", PropertyValue::Map(map)) => {
+                        replication = Some(replication_strategy(map)?)
+                    }
+                    ("durable_writes", PropertyValue::Constant(Constant::Bool(value))) => {
+                        durable_writes = Some(value)
+                    }
+                    ("durable_writes", PropertyValue::Constant(Constant::String(value))) => {
+                        durable_writes = Some(value.parse().ok()?)
+                    }
+                    _ => return None,
+                }
+            }
+            Some((replication, durable_writes))
+        },
+    )(i)
+}
+
+/// Interprets a replication map, falling back to `ReplicationStrategy::Other` for anything unrecognized.
+/// Returns `None` if there is no `class`.
+fn replication_strategy(map: Vec<(Constant, Constant)>) -> Option<ReplicationStrategy> {
+    let mut class = None;
+    let mut options = vec![];
+    for (key, value) in &map {
+        match (constant_value(key)?, constant_value(value)?) {
+            (key, value) if key == "class" => class = Some(value),
+            option => options.push(option),
+        }
+    }
+    let class = class?;
+
+    let factors = options
+        .iter()
+        .map(|(name, factor)| Some((name.clone(), factor.parse::<u64>().ok()?)))
+        .collect::<Option<Vec<_>>>();
+    let strategy = match (class.rsplit('.').next(), factors) {
+        (Some("SimpleStrategy"), Some(factors))
+            if factors.len() == 1 && factors[0].0 == "replication_factor" =>
+        {
+            ReplicationStrategy::Simple {
+                replication_factor: factors[0].1,
+            }
+        }
+        (Some("NetworkTopologyStrategy"), Some(mut datacenters)) => {
+            let replication_factor = datacenters
+                .iter()
+                .position(|(name, _)| name == "replication_factor")
+                .map(|index| datacenters.remove(index).1);
+            ReplicationStrategy::NetworkTopology {
+                replication_factor,
+                datacenters,
+            }
+        }
+        _ => ReplicationStrategy::Other { class, options },
+    };
+    Some(strategy)
+}
+
+fn constant_value(constant: &Constant) -> Option<String> {
+    match constant {
+        Constant::String(value) => Some(value.clone()),
+        Constant::Decimal(value) => Some(value.to_string()),
+        Constant::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
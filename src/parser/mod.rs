@@ -1,2 +1,21 @@
+pub(crate) mod batch;
+pub(crate) mod common;
+pub(crate) mod cql_type;
+pub(crate) mod delete;
+pub(crate) mod describe;
+pub(crate) mod function;
+pub(crate) mod index;
 pub(crate) mod insert;
+pub(crate) mod keyspace;
+pub(crate) mod permission;
+pub(crate) mod role;
 pub(crate) mod select;
+pub(crate) mod shell;
+pub(crate) mod table;
+pub(crate) mod trigger;
+pub(crate) mod udt;
+pub(crate) mod update;
+pub(crate) mod view;
+
+/// The input to every parser, which tracks its byte offset into the original input so nodes can record their spans
+pub(crate) type Input<'a> = nom_locate::LocatedSpan<&'a [u8]>;
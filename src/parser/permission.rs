@@ -0,0 +1,163 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, opt};
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    identifier, parenthesized_list, qualified_name, string_constant, whitespace0, whitespace1,
+};
+use crate::parser::cql_type::cql_type;
+use crate::parser::role::{of_role, recursive, role_name};
+use crate::parser::table::table_keyword;
+use crate::parser::Input;
+
+pub fn grant_permission(i: Input) -> IResult<Input, GrantPermission> {
+    let (i, (_, _, permission, _)) = tuple((
+        tag_no_case("grant"),
+        whitespace1,
+        permission,
+        tuple((whitespace1, tag_no_case("on"), whitespace1)),
+    ))(i)?;
+    let (remaining_input, (resource, _, grantee)) = cut(tuple((
+        resource,
+        tuple((whitespace1, tag_no_case("to"), whitespace1)),
+        role_name,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        GrantPermission {
+            permission,
+            resource,
+            grantee,
+        },
+    ))
+}
+
+pub fn revoke_permission(i: Input) -> IResult<Input, RevokePermission> {
+    let (i, (_, _, permission, _)) = tuple((
+        tag_no_case("revoke"),
+        whitespace1,
+        permission,
+        tuple((whitespace1, tag_no_case("on"), whitespace1)),
+    ))(i)?;
+    let (remaining_input, (resource, _, revokee)) = cut(tuple((
+        resource,
+        tuple((whitespace1, tag_no_case("from"), whitespace1)),
+        role_name,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        RevokePermission {
+            permission,
+            resource,
+            revokee,
+        },
+    ))
+}
+
+pub fn list_permissions(i: Input) -> IResult<Input, ListPermissions> {
+    let (i, (_, _, permission)) = tuple((tag_no_case("list"), whitespace1, permission))(i)?;
+    let (remaining_input, (resource, of, recursive)) = cut(tuple((
+        opt(preceded(
+            tuple((whitespace1, tag_no_case("on"), whitespace1)),
+            resource,
+        )),
+        of_role,
+        recursive,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        ListPermissions {
+            permission,
+            resource,
+            of,
+            recursive,
+        },
+    ))
+}
+
+/// Parses a permission, optionally followed by `PERMISSION` or `PERMISSIONS`
+fn permission(i: Input) -> IResult<Input, Permission> {
+    terminated(
+        alt((
+            map(tag_no_case("all"), |_| Permission::All),
+            map(tag_no_case("create"), |_| Permission::Create),
+            map(tag_no_case("alter"), |_| Permission::Alter),
+            map(tag_no_case("drop"), |_| Permission::Drop),
+            map(tag_no_case("select_masked"), |_| Permission::SelectMasked),
+            map(tag_no_case("select"), |_| Permission::Select),
+            map(tag_no_case("modify"), |_| Permission::Modify),
+            map(tag_no_case("authorize"), |_| Permission::Authorize),
+            map(tag_no_case("describe"), |_| Permission::Describe),
+            map(tag_no_case("execute"), |_| Permission::Execute),
+            map(tag_no_case("unmask"), |_| Permission::Unmask),
+        )),
+        opt(pair(
+            whitespace1,
+            alt((tag_no_case("permissions"), tag_no_case("permission"))),
+        )),
+    )(i)
+}
+
+fn resource(i: Input) -> IResult<Input, Resource> {
+    let all = |keyword| tuple((tag_no_case("all"), whitespace1, tag_no_case(keyword)));
+    alt((
+        map(all("keyspaces"), |_| Resource::AllKeyspaces),
+        map(all("roles"), |_| Resource::AllRoles),
+        map(
+            preceded(
+                tuple((
+                    all("functions"),
+                    whitespace1,
+                    tag_no_case("in"),
+                    whitespace1,
+                    tag_no_case("keyspace"),
+                    whitespace1,
+                )),
+                identifier,
+            ),
+            Resource::AllFunctionsInKeyspace,
+        ),
+        map(all("functions"), |_| Resource::AllFunctions),
+        map(all("mbeans"), |_| Resource::AllMbeans),
+        map(
+            preceded(pair(tag_no_case("keyspace"), whitespace1), identifier),
+            Resource::Keyspace,
+        ),
+        map(
+            preceded(pair(table_keyword, whitespace1), qualified_name),
+            Resource::Table,
+        ),
+        map(
+            preceded(pair(tag_no_case("role"), whitespace1), role_name),
+            Resource::Role,
+        ),
+        map(
+            preceded(
+                pair(tag_no_case("function"), whitespace1),
+                pair(
+                    qualified_name,
+                    preceded(whitespace0, parenthesized_list(cql_type)),
+                ),
+            ),
+            |(name, argument_types)| Resource::Function {
+                name,
+                argument_types,
+            },
+        ),
+        map(
+            preceded(pair(tag_no_case("mbeans"), whitespace1), string_constant),
+            Resource::Mbeans,
+        ),
+        map(
+            preceded(pair(tag_no_case("mbean"), whitespace1), string_constant),
+            Resource::Mbean,
+        ),
+        map(qualified_name, Resource::Table),
+    ))(i)
+}
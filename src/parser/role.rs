@@ -0,0 +1,248 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::combinator::{cut, map, opt};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, pair, preceded, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    bool_constant, if_exists, if_not_exists, name, property_map, spanned, string_constant,
+    whitespace0, whitespace1, ws_sep_and, ws_sep_comma,
+};
+use crate::parser::Input;
+
+pub fn create_role(i: Input) -> IResult<Input, CreateRole> {
+    let (i, _) = tuple((tag_no_case("create"), whitespace1, tag_no_case("role")))(i)?;
+    let (remaining_input, (if_not_exists, _, name, options)) = cut(tuple((
+        if_not_exists,
+        whitespace1,
+        role_name,
+        opt(role_options),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        CreateRole {
+            if_not_exists,
+            name,
+            options: options.unwrap_or_default(),
+        },
+    ))
+}
+
+pub fn alter_role(i: Input) -> IResult<Input, AlterRole> {
+    let (i, _) = tuple((tag_no_case("alter"), whitespace1, tag_no_case("role")))(i)?;
+    let (remaining_input, (_, name, options)) =
+        cut(tuple((whitespace1, role_name, role_options)))(i)?;
+    Ok((remaining_input, AlterRole { name, options }))
+}
+
+pub fn drop_role(i: Input) -> IResult<Input, DropRole> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("role")))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, role_name)))(i)?;
+    Ok((remaining_input, DropRole { if_exists, name }))
+}
+
+pub fn create_user(i: Input) -> IResult<Input, CreateUser> {
+    let (i, _) = tuple((tag_no_case("create"), whitespace1, tag_no_case("user")))(i)?;
+    let (remaining_input, (if_not_exists, _, name, password, superuser)) = cut(tuple((
+        if_not_exists,
+        whitespace1,
+        role_name,
+        user_password,
+        user_superuser,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        CreateUser {
+            if_not_exists,
+            name,
+            password,
+            superuser,
+        },
+    ))
+}
+
+pub fn alter_user(i: Input) -> IResult<Input, AlterUser> {
+    let (i, _) = tuple((tag_no_case("alter"), whitespace1, tag_no_case("user")))(i)?;
+    let (remaining_input, (_, name, password, superuser)) = cut(tuple((
+        whitespace1,
+        role_name,
+        user_password,
+        user_superuser,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        AlterUser {
+            name,
+            password,
+            superuser,
+        },
+    ))
+}
+
+pub fn drop_user(i: Input) -> IResult<Input, DropUser> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("user")))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, role_name)))(i)?;
+    Ok((remaining_input, DropUser { if_exists, name }))
+}
+
+pub fn grant_role(i: Input) -> IResult<Input, GrantRole> {
+    let (i, (_, _, role, _)) = tuple((
+        tag_no_case("grant"),
+        whitespace1,
+        role_name,
+        tuple((whitespace1, tag_no_case("to"))),
+    ))(i)?;
+    let (remaining_input, grantee) = cut(preceded(whitespace1, role_name))(i)?;
+    Ok((remaining_input, GrantRole { role, grantee }))
+}
+
+pub fn revoke_role(i: Input) -> IResult<Input, RevokeRole> {
+    let (i, (_, _, role, _)) = tuple((
+        tag_no_case("revoke"),
+        whitespace1,
+        role_name,
+        tuple((whitespace1, tag_no_case("from"))),
+    ))(i)?;
+    let (remaining_input, revokee) = cut(preceded(whitespace1, role_name))(i)?;
+    Ok((remaining_input, RevokeRole { role, revokee }))
+}
+
+pub fn list_roles(i: Input) -> IResult<Input, ListRoles> {
+    let (i, _) = tuple((tag_no_case("list"), whitespace1, tag_no_case("roles")))(i)?;
+    let (remaining_input, (of, recursive)) = cut(pair(of_role, recursive))(i)?;
+    Ok((remaining_input, ListRoles { of, recursive }))
+}
+
+pub fn list_users(i: Input) -> IResult<Input, ()> {
+    map(
+        tuple((tag_no_case("list"), whitespace1, tag_no_case("users"))),
+        |_| (),
+    )(i)
+}
+
+/// Parses a role or user name, which may be given as an identifier or a string
+pub fn role_name(i: Input) -> IResult<Input, Identifier> {
+    map(spanned(alt((string_constant, name))), |(value, span)| {
+        Identifier { value, span }
+    })(i)
+}
+
+/// Parses `OF role`, including its leading whitespace.
+pub fn of_role(i: Input) -> IResult<Input, Option<Identifier>> {
+    opt(preceded(
+        tuple((whitespace1, tag_no_case("of"), whitespace1)),
+        role_name,
+    ))(i)
+}
+
+/// Parses the optional `NORECURSIVE`, including its leading whitespace, returning false if it is present.
+pub fn recursive(i: Input) -> IResult<Input, bool> {
+    map(
+        opt(pair(whitespace1, tag_no_case("norecursive"))),
+        |norecursive| norecursive.is_none(),
+    )(i)
+}
+
+/// Parses `WITH option AND ...`, including its leading whitespace.
+fn role_options(i: Input) -> IResult<Input, Vec<RoleOption>> {
+    preceded(
+        tuple((whitespace1, tag_no_case("with"), whitespace1)),
+        separated_list1(ws_sep_and, role_option),
+    )(i)
+}
+
+fn role_option(i: Input) -> IResult<Input, RoleOption> {
+    let equals = || tuple((whitespace0, tag("="), whitespace0));
+    alt((
+        map(
+            preceded(
+                tuple((
+                    tag_no_case("hashed"),
+                    whitespace1,
+                    tag_no_case("password"),
+                    equals(),
+                )),
+                string_constant,
+            ),
+            RoleOption::HashedPassword,
+        ),
+        map(
+            preceded(pair(tag_no_case("password"), equals()), string_constant),
+            RoleOption::Password,
+        ),
+        map(
+            preceded(pair(tag_no_case("login"), equals()), bool_constant),
+            RoleOption::Login,
+        ),
+        map(
+            preceded(pair(tag_no_case("superuser"), equals()), bool_constant),
+            RoleOption::Superuser,
+        ),
+        map(
+            preceded(pair(tag_no_case("options"), equals()), property_map),
+            RoleOption::Options,
+        ),
+        map(
+            tuple((
+                tag_no_case("access"),
+                whitespace1,
+                tag_no_case("to"),
+                whitespace1,
+                tag_no_case("all"),
+                whitespace1,
+                tag_no_case("datacenters"),
+            )),
+            |_| RoleOption::AccessToAllDatacenters,
+        ),
+        map(
+            preceded(
+                tuple((
+                    tag_no_case("access"),
+                    whitespace1,
+                    tag_no_case("to"),
+                    whitespace1,
+                    tag_no_case("datacenters"),
+                    whitespace0,
+                )),
+                delimited(
+                    pair(tag("{"), whitespace0),
+                    separated_list1(ws_sep_comma, string_constant),
+                    pair(whitespace0, tag("}")),
+                ),
+            ),
+            RoleOption::AccessToDatacenters,
+        ),
+    ))(i)
+}
+
+/// Parses the `WITH PASSWORD 'password'` of the legacy user statements, including its leading whitespace.
+fn user_password(i: Input) -> IResult<Input, Option<String>> {
+    opt(preceded(
+        tuple((
+            whitespace1,
+            tag_no_case("with"),
+            whitespace1,
+            tag_no_case("password"),
+            whitespace1,
+        )),
+        string_constant,
+    ))(i)
+}
+
+/// Parses `SUPERUSER` or `NOSUPERUSER`, including its leading whitespace.
+fn user_superuser(i: Input) -> IResult<Input, Option<bool>> {
+    opt(preceded(
+        whitespace1,
+        alt((
+            map(tag_no_case("superuser"), |_| true),
+            map(tag_no_case("nosuperuser"), |_| false),
+        )),
+    ))(i)
+}
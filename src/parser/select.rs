@@ -1,228 +1,323 @@
-use std::str;
-use std::str::FromStr;
-
 use nom::branch::alt;
-use nom::bytes::complete::{is_not, tag, tag_no_case, take_while1};
-use nom::character::complete::{digit1, multispace0, multispace1};
-use nom::character::is_alphanumeric;
-use nom::combinator::{map, opt};
-use nom::multi::{fold_many0, many0};
-use nom::sequence::{delimited, pair, preceded, terminated, tuple};
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cond, cut, map, opt, recognize};
+use nom::multi::{many0, separated_list1};
+use nom::sequence::{pair, preceded, terminated, tuple};
 use nom::IResult;
 
 use crate::ast::*;
+use crate::parser::common::{
+    bind_marker, expr, identifier, qualified_name, relation, relations, spanned, unsigned_number,
+    using_clause, whitespace1, ws_sep_and, ws_sep_comma,
+};
+use crate::parser::Input;
 
-pub fn select(i: &[u8]) -> IResult<&[u8], Select> {
-    let (
-        remaining_input,
-        (_, _, distinct, json, select, from, where_, order_by, limit, allow_filtering),
-    ) = tuple((
-        tag_no_case("select"),
-        multispace1,
-        distinct,
-        json,
-        fields,
-        from,
-        where_,
-        opt(order_by),
-        limit,
-        allow_filtering,
-    ))(i)?;
-    Ok((
-        remaining_input,
-        Select {
-            distinct,
+/// Parses a SELECT statement.
+///
+/// When `strict` is set the optional clauses must appear in the order Cassandra requires
+/// (see [`SelectClause`]) and list separators are mandatory.
+/// Parsing stops at the first repeated or, when `strict` is set, out of order clause, leaving it in the remaining input.
+pub fn select(strict: bool) -> impl Fn(Input) -> IResult<Input, Select> {
+    move |i| {
+        let (i, _) = tag_no_case("select")(i)?;
+        let (mut remaining_input, (_, distinct_first, json, distinct, select, from)) =
+            cut(tuple((
+                whitespace1,
+                cond(!strict, distinct),
+                json,
+                distinct,
+                fields(strict),
+                from,
+            )))(i)?;
+
+        let mut select = Select {
+            distinct: distinct_first.unwrap_or(false) || distinct,
             json,
             select,
             from,
-            where_,
-            order_by,
-            limit,
-            allow_filtering,
-        },
-    ))
-}
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+            using: None,
+        };
 
-pub fn json(i: &[u8]) -> IResult<&[u8], bool> {
-    map(opt(terminated(tag_no_case("json"), multispace1)), |v| {
-        v.is_some()
-    })(i)
-}
+        let mut seen: Vec<SelectClause> = vec![];
+        loop {
+            match clause(strict)(remaining_input) {
+                Ok((remaining, clause)) => {
+                    let kind = clause.kind();
+                    let out_of_order = seen.last().is_some_and(|previous| kind <= *previous);
+                    if seen.contains(&kind) || strict && out_of_order {
+                        break;
+                    }
+                    seen.push(kind);
+                    clause.apply(&mut select);
+                    remaining_input = remaining;
+                }
+                Err(nom::Err::Error(_)) => break,
+                Err(err) => return Err(err),
+            }
+        }
 
-pub fn distinct(i: &[u8]) -> IResult<&[u8], bool> {
-    map(opt(terminated(tag_no_case("distinct"), multispace1)), |v| {
-        v.is_some()
-    })(i)
+        Ok((remaining_input, select))
+    }
 }
 
-pub fn where_(i: &[u8]) -> IResult<&[u8], Vec<RelationElement>> {
-    map(
-        opt(preceded(
-            tuple((multispace1, tag_no_case("where"), multispace1)),
-            where_elements,
-        )),
-        |x| x.unwrap_or_default(),
-    )(i)
+/// The clauses that may follow `FROM`, in the order Cassandra requires them to appear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SelectClause {
+    Where,
+    GroupBy,
+    OrderBy,
+    PerPartitionLimit,
+    Limit,
+    AllowFiltering,
+    Using,
 }
 
-pub fn where_elements(i: &[u8]) -> IResult<&[u8], Vec<RelationElement>> {
-    many0(terminated(
-        where_element,
-        opt(tuple((multispace1, tag_no_case("AND"), multispace1))), // TODO: this seems wrong
-    ))(i)
-}
+impl SelectClause {
+    pub fn name(self) -> &'static str {
+        match self {
+            SelectClause::Where => "WHERE",
+            SelectClause::GroupBy => "GROUP BY",
+            SelectClause::OrderBy => "ORDER BY",
+            SelectClause::PerPartitionLimit => "PER PARTITION LIMIT",
+            SelectClause::Limit => "LIMIT",
+            SelectClause::AllowFiltering => "ALLOW FILTERING",
+            SelectClause::Using => "USING",
+        }
+    }
 
-pub fn where_element(i: &[u8]) -> IResult<&[u8], RelationElement> {
-    let (remaining_input, (lhs, _, operator, _, rhs)) =
-        tuple((expr, multispace1, operator, multispace1, expr))(i)?;
+    /// Returns whether the clause is present in `select`.
+    pub fn is_present(self, select: &Select) -> bool {
+        match self {
+            SelectClause::Where => !select.where_.is_empty(),
+            SelectClause::GroupBy => !select.group_by.is_empty(),
+            SelectClause::OrderBy => !select.order_by.is_empty(),
+            SelectClause::PerPartitionLimit => select.per_partition_limit.is_some(),
+            SelectClause::Limit => select.limit.is_some(),
+            SelectClause::AllowFiltering => select.allow_filtering,
+            SelectClause::Using => select.using.is_some(),
+        }
+    }
 
-    Ok((
-        remaining_input,
-        RelationElement::Comparison(RelationComparison { lhs, operator, rhs }),
-    ))
+    /// Returns the last clause, in canonical order, that is present in `select`.
+    pub fn last_present(select: &Select) -> Option<SelectClause> {
+        [
+            SelectClause::Using,
+            SelectClause::AllowFiltering,
+            SelectClause::Limit,
+            SelectClause::PerPartitionLimit,
+            SelectClause::OrderBy,
+            SelectClause::GroupBy,
+            SelectClause::Where,
+        ]
+        .into_iter()
+        .find(|clause| clause.is_present(select))
+    }
 }
 
-pub fn order_by(i: &[u8]) -> IResult<&[u8], OrderBy> {
-    let (remaining_input, (_, _, _, _, _, name, ordering)) = tuple((
-        multispace1,
-        tag_no_case("order"),
-        multispace1,
-        tag_no_case("by"),
-        multispace1,
-        identifier,
-        opt(preceded(multispace1, ordering)),
-    ))(i)?;
-
-    let name = String::from_utf8(name.to_vec()).unwrap();
-    let ordering = ordering.unwrap_or(Ordering::Asc);
-    Ok((remaining_input, OrderBy { name, ordering }))
+pub enum Clause {
+    Where(Vec<RelationElement>),
+    GroupBy(Vec<Identifier>),
+    OrderBy(Vec<OrderBy>),
+    PerPartitionLimit(Limit),
+    Limit(Limit),
+    AllowFiltering,
+    Using(UsingClause),
 }
 
-pub fn operator(i: &[u8]) -> IResult<&[u8], ComparisonOperator> {
-    alt((
-        map(tag("="), |_| ComparisonOperator::Equals),
-        map(tag(">="), |_| ComparisonOperator::GreaterThanOrEqualTo),
-        map(tag(">"), |_| ComparisonOperator::GreaterThan),
-        map(tag("<="), |_| ComparisonOperator::LessThanOrEqualTo),
-        map(tag("<"), |_| ComparisonOperator::LessThan),
-    ))(i)
+impl Clause {
+    pub fn kind(&self) -> SelectClause {
+        match self {
+            Clause::Where(_) => SelectClause::Where,
+            Clause::GroupBy(_) => SelectClause::GroupBy,
+            Clause::OrderBy(_) => SelectClause::OrderBy,
+            Clause::PerPartitionLimit(_) => SelectClause::PerPartitionLimit,
+            Clause::Limit(_) => SelectClause::Limit,
+            Clause::AllowFiltering => SelectClause::AllowFiltering,
+            Clause::Using(_) => SelectClause::Using,
+        }
+    }
+
+    fn apply(self, select: &mut Select) {
+        match self {
+            Clause::Where(where_) => select.where_ = where_,
+            Clause::GroupBy(group_by) => select.group_by = group_by,
+            Clause::OrderBy(order_by) => select.order_by = order_by,
+            Clause::PerPartitionLimit(limit) => select.per_partition_limit = Some(limit),
+            Clause::Limit(limit) => select.limit = Some(limit),
+            Clause::AllowFiltering => select.allow_filtering = true,
+            Clause::Using(using) => select.using = Some(using),
+        }
+    }
 }
 
-pub fn ordering(i: &[u8]) -> IResult<&[u8], Ordering> {
-    alt((
-        map(tag_no_case("asc"), |_| Ordering::Asc),
-        map(tag_no_case("desc"), |_| Ordering::Desc),
-    ))(i)
+/// Parses a single clause following `FROM`, including its leading whitespace.
+pub fn clause(strict: bool) -> impl Fn(Input) -> IResult<Input, Clause> {
+    move |i| {
+        alt((
+            preceded(
+                whitespace1,
+                alt((
+                    map(where_(strict), Clause::Where),
+                    map(group_by, Clause::GroupBy),
+                    map(order_by, Clause::OrderBy),
+                    map(per_partition_limit, Clause::PerPartitionLimit),
+                    map(limit, Clause::Limit),
+                    map(allow_filtering, |_| Clause::AllowFiltering),
+                )),
+            ),
+            map(using_clause, Clause::Using),
+        ))(i)
+    }
 }
 
-pub fn limit(i: &[u8]) -> IResult<&[u8], Option<u64>> {
-    opt(preceded(
-        tuple((multispace1, tag_no_case("limit"), multispace1)),
-        unsigned_number,
-    ))(i)
+pub fn json(i: Input) -> IResult<Input, bool> {
+    map(opt(terminated(tag_no_case("json"), whitespace1)), |v| {
+        v.is_some()
+    })(i)
 }
 
-pub fn unsigned_number(i: &[u8]) -> IResult<&[u8], u64> {
-    map(digit1, |d| {
-        FromStr::from_str(str::from_utf8(d).unwrap()).unwrap()
+pub fn distinct(i: Input) -> IResult<Input, bool> {
+    map(opt(terminated(tag_no_case("distinct"), whitespace1)), |v| {
+        v.is_some()
     })(i)
 }
 
-pub fn allow_filtering(i: &[u8]) -> IResult<&[u8], bool> {
-    opt(preceded(multispace1, tag_no_case("allow filtering")))(i).map(|(r, v)| (r, v.is_some()))
+pub fn where_(strict: bool) -> impl Fn(Input) -> IResult<Input, Vec<RelationElement>> {
+    move |i| {
+        preceded(
+            pair(tag_no_case("where"), whitespace1),
+            where_elements(strict),
+        )(i)
+    }
 }
 
-pub fn fields(i: &[u8]) -> IResult<&[u8], Vec<SelectElement>> {
-    many0(terminated(field, opt(ws_sep_comma)))(i) // TODO: this seems wrong
+pub fn where_elements(strict: bool) -> impl Fn(Input) -> IResult<Input, Vec<RelationElement>> {
+    move |i| {
+        if strict {
+            relations(i)
+        } else {
+            many0(terminated(relation, opt(ws_sep_and)))(i)
+        }
+    }
 }
 
-pub fn field(i: &[u8]) -> IResult<&[u8], SelectElement> {
-    let (remaining, (expr, as_alias)) = pair(
-        expr,
-        opt(preceded(
-            tuple((multispace1, tag_no_case("AS"), multispace1)),
-            identifier,
+pub fn group_by(i: Input) -> IResult<Input, Vec<Identifier>> {
+    preceded(
+        tuple((
+            tag_no_case("group"),
+            whitespace1,
+            tag_no_case("by"),
+            whitespace1,
         )),
-    )(i)?;
-
-    let as_alias = as_alias.map(|x| String::from_utf8(x.to_vec()).unwrap());
-    Ok((remaining, SelectElement { expr, as_alias }))
+        separated_list1(ws_sep_comma, identifier),
+    )(i)
 }
 
-pub fn from(i: &[u8]) -> IResult<&[u8], Vec<String>> {
+pub fn order_by(i: Input) -> IResult<Input, Vec<OrderBy>> {
     preceded(
-        tuple((multispace1, tag_no_case("from"), multispace1)),
-        map(identifier, |name| {
-            vec![String::from_utf8(name.to_vec()).unwrap()]
-        }),
+        tuple((
+            tag_no_case("order"),
+            whitespace1,
+            tag_no_case("by"),
+            whitespace1,
+        )),
+        separated_list1(ws_sep_comma, order_by_element),
     )(i)
 }
 
-pub fn expr(i: &[u8]) -> IResult<&[u8], Expr> {
-    alt((
-        map(tag("*"), |_| Expr::Wildcard),
-        map(constant, Expr::Constant),
-        map(identifier, |name| {
-            Expr::Name(String::from_utf8(name.to_vec()).unwrap())
-        }),
-    ))(i)
+pub fn order_by_element(i: Input) -> IResult<Input, OrderBy> {
+    let (remaining_input, ((name, ordering), span)) =
+        spanned(pair(identifier, opt(preceded(whitespace1, ordering))))(i)?;
+
+    let ordering = ordering.unwrap_or(Ordering::Asc);
+    Ok((
+        remaining_input,
+        OrderBy {
+            name,
+            ordering,
+            span,
+        },
+    ))
 }
 
-pub fn constant(i: &[u8]) -> IResult<&[u8], Constant> {
+pub fn ordering(i: Input) -> IResult<Input, Ordering> {
     alt((
-        map(integer_constant, Constant::Decimal),
-        map(string_constant, Constant::String),
-        map(bool_constant, Constant::Bool),
+        map(tag_no_case("asc"), |_| Ordering::Asc),
+        map(tag_no_case("desc"), |_| Ordering::Desc),
     ))(i)
 }
 
-pub fn integer_constant(i: &[u8]) -> IResult<&[u8], i64> {
-    map(pair(opt(tag("-")), digit1), |(negative, bytes)| {
-        let mut intval = i64::from_str(str::from_utf8(bytes).unwrap()).unwrap();
-        if (negative).is_some() {
-            intval *= -1;
-        }
-        intval
-    })(i)
+pub fn per_partition_limit(i: Input) -> IResult<Input, Limit> {
+    preceded(
+        tuple((
+            tag_no_case("per"),
+            whitespace1,
+            tag_no_case("partition"),
+            whitespace1,
+            tag_no_case("limit"),
+            whitespace1,
+        )),
+        limit_value,
+    )(i)
 }
 
-pub fn string_constant(i: &[u8]) -> IResult<&[u8], String> {
-    map(raw_string_quoted, |bytes| String::from_utf8(bytes).unwrap())(i)
-}
-
-fn raw_string_quoted(i: &[u8]) -> IResult<&[u8], Vec<u8>> {
-    delimited(
-        tag("'"),
-        fold_many0(
-            alt((
-                is_not("'"), //
-                map(tag("''"), |_| &b"'"[..]),
-            )),
-            Vec::new,
-            |mut acc: Vec<u8>, bytes: &[u8]| {
-                acc.extend(bytes);
-                acc
-            },
-        ),
-        tag("'"),
-    )(i)
+pub fn limit(i: Input) -> IResult<Input, Limit> {
+    preceded(pair(tag_no_case("limit"), whitespace1), limit_value)(i)
 }
 
-pub fn bool_constant(i: &[u8]) -> IResult<&[u8], bool> {
+pub fn limit_value(i: Input) -> IResult<Input, Limit> {
     alt((
-        map(tag_no_case("true"), |_| true),
-        map(tag_no_case("false"), |_| false),
+        map(unsigned_number, Limit::Literal),
+        map(bind_marker, Limit::BindMarker),
     ))(i)
 }
 
-pub(crate) fn ws_sep_comma(i: &[u8]) -> IResult<&[u8], &[u8]> {
-    delimited(multispace0, tag(","), multispace0)(i)
+pub fn allow_filtering(i: Input) -> IResult<Input, Input> {
+    recognize(tuple((
+        tag_no_case("allow"),
+        whitespace1,
+        tag_no_case("filtering"),
+    )))(i)
 }
 
-pub fn identifier(i: &[u8]) -> IResult<&[u8], &[u8]> {
-    take_while1(is_identifier)(i)
+pub fn fields(strict: bool) -> impl Fn(Input) -> IResult<Input, Vec<SelectElement>> {
+    move |i| {
+        if strict {
+            separated_list1(ws_sep_comma, field)(i)
+        } else {
+            many0(terminated(field, opt(ws_sep_comma)))(i)
+        }
+    }
 }
 
-pub fn is_identifier(chr: u8) -> bool {
-    is_alphanumeric(chr) || chr == b'_'
+pub fn field(i: Input) -> IResult<Input, SelectElement> {
+    let (remaining, ((expr, as_alias), span)) = spanned(pair(
+        expr,
+        opt(preceded(
+            tuple((whitespace1, tag_no_case("AS"), whitespace1)),
+            identifier,
+        )),
+    ))(i)?;
+
+    Ok((
+        remaining,
+        SelectElement {
+            expr,
+            as_alias,
+            span,
+        },
+    ))
+}
+
+pub fn from(i: Input) -> IResult<Input, QualifiedName> {
+    preceded(
+        tuple((whitespace1, tag_no_case("from"), whitespace1)),
+        qualified_name,
+    )(i)
 }
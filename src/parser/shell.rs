@@ -0,0 +1,148 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, opt};
+use nom::sequence::{pair, preceded, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    identifier, parenthesized_list, properties, qualified_name, string_constant, unsigned_number,
+    whitespace0, whitespace1,
+};
+use crate::parser::Input;
+
+pub fn shell_command(i: Input) -> IResult<Input, ShellCommand> {
+    alt((
+        map(
+            preceded(tag_no_case("consistency"), opt(argument(consistency_level))),
+            ShellCommand::Consistency,
+        ),
+        map(
+            preceded(
+                tuple((
+                    tag_no_case("serial"),
+                    whitespace1,
+                    tag_no_case("consistency"),
+                )),
+                opt(argument(consistency_level)),
+            ),
+            ShellCommand::SerialConsistency,
+        ),
+        map(
+            preceded(tag_no_case("tracing"), opt(argument(switch))),
+            ShellCommand::Tracing,
+        ),
+        map(
+            preceded(
+                tag_no_case("paging"),
+                opt(argument(alt((
+                    map(switch, |on| if on { Paging::On } else { Paging::Off }),
+                    map(unsigned_number, Paging::PageSize),
+                )))),
+            ),
+            ShellCommand::Paging,
+        ),
+        map(
+            preceded(tag_no_case("expand"), opt(argument(switch))),
+            ShellCommand::Expand,
+        ),
+        map(
+            preceded(
+                tag_no_case("source"),
+                cut(preceded(whitespace1, string_constant)),
+            ),
+            ShellCommand::Source,
+        ),
+        map(
+            preceded(
+                tag_no_case("capture"),
+                opt(argument(alt((
+                    map(string_constant, Capture::File),
+                    map(tag_no_case("off"), |_| Capture::Off),
+                )))),
+            ),
+            ShellCommand::Capture,
+        ),
+        map(copy, ShellCommand::Copy),
+    ))(i)
+}
+
+fn copy(i: Input) -> IResult<Input, Copy> {
+    let (i, _) = tag_no_case("copy")(i)?;
+    let (remaining_input, (_, table, columns, _, (direction, file), options)) = cut(tuple((
+        whitespace1,
+        qualified_name,
+        opt(preceded(whitespace0, parenthesized_list(identifier))),
+        whitespace1,
+        alt((
+            pair(
+                map(tag_no_case("to"), |_| CopyDirection::To),
+                preceded(whitespace1, copy_file("stdout")),
+            ),
+            pair(
+                map(tag_no_case("from"), |_| CopyDirection::From),
+                preceded(whitespace1, copy_file("stdin")),
+            ),
+        )),
+        opt(preceded(
+            tuple((whitespace1, tag_no_case("with"), whitespace1)),
+            properties,
+        )),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        Copy {
+            table,
+            columns: columns.unwrap_or_default(),
+            direction,
+            file,
+            options: options.unwrap_or_default(),
+        },
+    ))
+}
+
+/// Parses a file name or the given standard stream, returning `None` for the latter
+fn copy_file(stream: &'static str) -> impl Fn(Input) -> IResult<Input, Option<String>> {
+    move |i| {
+        alt((
+            map(string_constant, Some),
+            map(tag_no_case(stream), |_| None),
+        ))(i)
+    }
+}
+
+/// Parses a command argument, including its leading whitespace
+fn argument<'a, O>(
+    parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, O> {
+    preceded(whitespace1, parser)
+}
+
+/// Parses `ON` or `OFF`
+fn switch(i: Input) -> IResult<Input, bool> {
+    alt((
+        map(tag_no_case("on"), |_| true),
+        map(tag_no_case("off"), |_| false),
+    ))(i)
+}
+
+fn consistency_level(i: Input) -> IResult<Input, ConsistencyLevel> {
+    alt((
+        map(tag_no_case("any"), |_| ConsistencyLevel::Any),
+        map(tag_no_case("one"), |_| ConsistencyLevel::One),
+        map(tag_no_case("two"), |_| ConsistencyLevel::Two),
+        map(tag_no_case("three"), |_| ConsistencyLevel::Three),
+        map(tag_no_case("quorum"), |_| ConsistencyLevel::Quorum),
+        map(tag_no_case("all"), |_| ConsistencyLevel::All),
+        map(tag_no_case("local_quorum"), |_| {
+            ConsistencyLevel::LocalQuorum
+        }),
+        map(tag_no_case("each_quorum"), |_| ConsistencyLevel::EachQuorum),
+        map(tag_no_case("serial"), |_| ConsistencyLevel::Serial),
+        map(tag_no_case("local_serial"), |_| {
+            ConsistencyLevel::LocalSerial
+        }),
+        map(tag_no_case("local_one"), |_| ConsistencyLevel::LocalOne),
+    ))(i)
+}
@@ -0,0 +1,264 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, map_opt, opt};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    identifier, if_exists, if_not_exists, parenthesized_list, parenthesized_list1, properties,
+    property, qualified_name, whitespace0, whitespace1, ws_sep_and,
+};
+use crate::parser::cql_type::cql_type;
+use crate::parser::select::order_by_element;
+use crate::parser::Input;
+
+pub fn create_table(i: Input) -> IResult<Input, CreateTable> {
+    let (i, _) = tuple((tag_no_case("create"), whitespace1, table_keyword))(i)?;
+    cut(map_opt(
+        tuple((
+            if_not_exists,
+            whitespace1,
+            qualified_name,
+            whitespace0,
+            parenthesized_list(table_element),
+            opt(preceded(
+                tuple((whitespace1, tag_no_case("with"), whitespace1)),
+                separated_list1(ws_sep_and, table_option),
+            )),
+        )),
+        |(if_not_exists, _, name, _, elements, options)| {
+            let mut table = CreateTable {
+                if_not_exists,
+                name,
+                columns: vec![],
+                partition_key: vec![],
+                clustering_key: vec![],
+                clustering_order: vec![],
+                compact_storage: false,
+                options: vec![],
+            };
+
+            let mut primary_key = None;
+            for element in elements {
+                match element {
+                    TableElement::Column(column, inline_primary_key) => {
+                        if inline_primary_key {
+                            if primary_key.is_some() {
+                                return None;
+                            }
+                            primary_key = Some((vec![column.name.clone()], vec![]));
+                        }
+                        table.columns.push(column);
+                    }
+                    TableElement::PrimaryKey(partition_key, clustering_key) => {
+                        if primary_key.is_some() {
+                            return None;
+                        }
+                        primary_key = Some((partition_key, clustering_key));
+                    }
+                }
+            }
+            (table.partition_key, table.clustering_key) = primary_key?;
+
+            for option in options.unwrap_or_default() {
+                match option {
+                    TableOption::ClusteringOrder(order) => table.clustering_order = order,
+                    TableOption::CompactStorage => table.compact_storage = true,
+                    TableOption::Property(property) => table.options.push(property),
+                }
+            }
+            Some(table)
+        },
+    ))(i)
+}
+
+pub fn alter_table(i: Input) -> IResult<Input, AlterTable> {
+    let (i, _) = tuple((tag_no_case("alter"), whitespace1, table_keyword))(i)?;
+    let (remaining_input, (if_exists, _, name, _, operation)) = cut(tuple((
+        if_exists,
+        whitespace1,
+        qualified_name,
+        whitespace1,
+        alter_table_operation,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        AlterTable {
+            if_exists,
+            name,
+            operation,
+        },
+    ))
+}
+
+fn alter_table_operation(i: Input) -> IResult<Input, AlterTableOperation> {
+    alt((
+        map(
+            preceded(
+                tag_no_case("add"),
+                alt((
+                    preceded(whitespace0, parenthesized_list(column_definition)),
+                    map(preceded(whitespace1, column_definition), |column| {
+                        vec![column]
+                    }),
+                )),
+            ),
+            AlterTableOperation::Add,
+        ),
+        map(
+            preceded(
+                tag_no_case("drop"),
+                alt((
+                    preceded(whitespace0, parenthesized_list(identifier)),
+                    map(preceded(whitespace1, identifier), |column| vec![column]),
+                )),
+            ),
+            AlterTableOperation::Drop,
+        ),
+        map(renames, AlterTableOperation::Rename),
+        map(
+            preceded(pair(tag_no_case("with"), whitespace1), properties),
+            AlterTableOperation::With,
+        ),
+    ))(i)
+}
+
+/// Parses `RENAME from TO to AND ...`
+pub fn renames(i: Input) -> IResult<Input, Vec<(Identifier, Identifier)>> {
+    preceded(
+        pair(tag_no_case("rename"), whitespace1),
+        separated_list1(
+            ws_sep_and,
+            separated_pair(
+                identifier,
+                tuple((whitespace1, tag_no_case("to"), whitespace1)),
+                identifier,
+            ),
+        ),
+    )(i)
+}
+
+pub fn drop_table(i: Input) -> IResult<Input, DropTable> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, table_keyword))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, qualified_name)))(i)?;
+    Ok((remaining_input, DropTable { if_exists, name }))
+}
+
+pub fn truncate(i: Input) -> IResult<Input, Truncate> {
+    let (i, _) = tag_no_case("truncate")(i)?;
+    let (remaining_input, (_, _, name)) = cut(tuple((
+        whitespace1,
+        opt(terminated(table_keyword, whitespace1)),
+        qualified_name,
+    )))(i)?;
+    Ok((remaining_input, Truncate { name }))
+}
+
+pub fn table_keyword(i: Input) -> IResult<Input, Input> {
+    alt((tag_no_case("table"), tag_no_case("columnfamily")))(i)
+}
+
+enum TableElement {
+    /// A column definition and whether it is declared as the `PRIMARY KEY`
+    Column(ColumnDefinition, bool),
+    /// `PRIMARY KEY ((partition_key, ...), clustering_key, ...)`
+    PrimaryKey(Vec<Identifier>, Vec<Identifier>),
+}
+
+fn table_element(i: Input) -> IResult<Input, TableElement> {
+    alt((
+        map(primary_key, |(partition_key, clustering_key)| {
+            TableElement::PrimaryKey(partition_key, clustering_key)
+        }),
+        map(
+            tuple((
+                column_definition,
+                opt(tuple((
+                    whitespace1,
+                    tag_no_case("primary"),
+                    whitespace1,
+                    tag_no_case("key"),
+                ))),
+            )),
+            |(column, primary_key)| TableElement::Column(column, primary_key.is_some()),
+        ),
+    ))(i)
+}
+
+pub fn column_definition(i: Input) -> IResult<Input, ColumnDefinition> {
+    map(
+        tuple((
+            identifier,
+            whitespace1,
+            cql_type,
+            opt(pair(whitespace1, tag_no_case("static"))),
+        )),
+        |(name, _, data_type, static_)| ColumnDefinition {
+            name,
+            data_type,
+            static_: static_.is_some(),
+        },
+    )(i)
+}
+
+/// Parses `PRIMARY KEY (pk, ck, ...)` or `PRIMARY KEY ((pk1, pk2), ck, ...)`
+pub fn primary_key(i: Input) -> IResult<Input, (Vec<Identifier>, Vec<Identifier>)> {
+    preceded(
+        tuple((
+            tag_no_case("primary"),
+            whitespace1,
+            tag_no_case("key"),
+            whitespace0,
+        )),
+        map_opt(
+            parenthesized_list1(alt((
+                parenthesized_list1(identifier),
+                map(identifier, |name| vec![name]),
+            ))),
+            |mut keys| {
+                let partition_key = keys.remove(0);
+                let clustering_key = keys
+                    .into_iter()
+                    .map(|mut key| (key.len() == 1).then(|| key.remove(0)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((partition_key, clustering_key))
+            },
+        ),
+    )(i)
+}
+
+pub enum TableOption {
+    ClusteringOrder(Vec<OrderBy>),
+    CompactStorage,
+    Property(Property),
+}
+
+pub fn table_option(i: Input) -> IResult<Input, TableOption> {
+    alt((
+        map(clustering_order, TableOption::ClusteringOrder),
+        map(
+            tuple((tag_no_case("compact"), whitespace1, tag_no_case("storage"))),
+            |_| TableOption::CompactStorage,
+        ),
+        map(property, TableOption::Property),
+    ))(i)
+}
+
+/// Parses `CLUSTERING ORDER BY (ck DESC, ...)`
+pub fn clustering_order(i: Input) -> IResult<Input, Vec<OrderBy>> {
+    preceded(
+        tuple((
+            tag_no_case("clustering"),
+            whitespace1,
+            tag_no_case("order"),
+            whitespace1,
+            tag_no_case("by"),
+            whitespace0,
+        )),
+        parenthesized_list(order_by_element),
+    )(i)
+}
@@ -0,0 +1,53 @@
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use nom::sequence::tuple;
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    identifier, if_exists, if_not_exists, qualified_name, string_constant, whitespace1,
+};
+use crate::parser::Input;
+
+pub fn create_trigger(i: Input) -> IResult<Input, CreateTrigger> {
+    let (i, _) = tuple((tag_no_case("create"), whitespace1, tag_no_case("trigger")))(i)?;
+    let (remaining_input, (if_not_exists, _, name, _, table, _, using)) = cut(tuple((
+        if_not_exists,
+        whitespace1,
+        identifier,
+        tuple((whitespace1, tag_no_case("on"), whitespace1)),
+        qualified_name,
+        tuple((whitespace1, tag_no_case("using"), whitespace1)),
+        string_constant,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        CreateTrigger {
+            if_not_exists,
+            name,
+            table,
+            using,
+        },
+    ))
+}
+
+pub fn drop_trigger(i: Input) -> IResult<Input, DropTrigger> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("trigger")))(i)?;
+    let (remaining_input, (if_exists, _, name, _, table)) = cut(tuple((
+        if_exists,
+        whitespace1,
+        identifier,
+        tuple((whitespace1, tag_no_case("on"), whitespace1)),
+        qualified_name,
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        DropTrigger {
+            if_exists,
+            name,
+            table,
+        },
+    ))
+}
@@ -0,0 +1,74 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map};
+use nom::sequence::{preceded, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    identifier, if_exists, if_not_exists, parenthesized_list, qualified_name, whitespace0,
+    whitespace1,
+};
+use crate::parser::cql_type::cql_type;
+use crate::parser::table::renames;
+use crate::parser::Input;
+
+pub fn create_type(i: Input) -> IResult<Input, CreateType> {
+    let (i, _) = tuple((tag_no_case("create"), whitespace1, tag_no_case("type")))(i)?;
+    let (remaining_input, (if_not_exists, _, name, _, fields)) = cut(tuple((
+        if_not_exists,
+        whitespace1,
+        qualified_name,
+        whitespace0,
+        parenthesized_list(field_definition),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        CreateType {
+            if_not_exists,
+            name,
+            fields,
+        },
+    ))
+}
+
+pub fn alter_type(i: Input) -> IResult<Input, AlterType> {
+    let (i, _) = tuple((tag_no_case("alter"), whitespace1, tag_no_case("type")))(i)?;
+    let (remaining_input, (if_exists, _, name, _, operation)) = cut(tuple((
+        if_exists,
+        whitespace1,
+        qualified_name,
+        whitespace1,
+        alt((
+            map(
+                preceded(tuple((tag_no_case("add"), whitespace1)), field_definition),
+                AlterTypeOperation::Add,
+            ),
+            map(renames, AlterTypeOperation::Rename),
+        )),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        AlterType {
+            if_exists,
+            name,
+            operation,
+        },
+    ))
+}
+
+pub fn drop_type(i: Input) -> IResult<Input, DropType> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, tag_no_case("type")))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, qualified_name)))(i)?;
+    Ok((remaining_input, DropType { if_exists, name }))
+}
+
+fn field_definition(i: Input) -> IResult<Input, FieldDefinition> {
+    map(
+        tuple((identifier, whitespace1, cql_type)),
+        |(name, _, data_type)| FieldDefinition { name, data_type },
+    )(i)
+}
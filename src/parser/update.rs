@@ -0,0 +1,97 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::combinator::{cut, map, opt, verify};
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, terminated, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    condition, expr, identifier, qualified_name, spanned, subscript, using_clause, where_clause,
+    whitespace0, whitespace1, ws_sep_comma,
+};
+use crate::parser::Input;
+
+pub fn update(i: Input) -> IResult<Input, Update> {
+    let (i, _) = tag_no_case("update")(i)?;
+    let (remaining_input, (_, table, using, _, _, _, set, where_, condition)) = cut(tuple((
+        whitespace1,
+        qualified_name,
+        opt(using_clause),
+        whitespace1,
+        tag_no_case("set"),
+        whitespace1,
+        separated_list1(ws_sep_comma, assignment),
+        where_clause,
+        opt(condition),
+    )))(i)?;
+
+    Ok((
+        remaining_input,
+        Update {
+            table,
+            using,
+            set,
+            where_,
+            condition,
+        },
+    ))
+}
+
+pub fn assignment(i: Input) -> IResult<Input, Assignment> {
+    let (remaining_input, ((lhs, (operator, rhs)), span)) = spanned(|i| {
+        let (i, lhs) = alt((map(subscript, Expr::Subscript), map(identifier, Expr::Name)))(i)?;
+        let column = match &lhs {
+            Expr::Name(name) => Some(name.as_str()),
+            _ => None,
+        };
+        let (i, operator_and_rhs) = preceded(
+            whitespace0,
+            alt((
+                map(preceded(terminated(tag("+="), whitespace0), expr), |rhs| {
+                    (AssignmentOperator::Add, rhs)
+                }),
+                map(preceded(terminated(tag("-="), whitespace0), expr), |rhs| {
+                    (AssignmentOperator::Subtract, rhs)
+                }),
+                preceded(terminated(tag("="), whitespace0), assignment_rhs(column)),
+            )),
+        )(i)?;
+        Ok((i, (lhs, operator_and_rhs)))
+    })(i)?;
+
+    Ok((
+        remaining_input,
+        Assignment {
+            lhs,
+            operator,
+            rhs,
+            span,
+        },
+    ))
+}
+
+/// Parses the right hand side of `c = ...`, where `c + x`, `c - x` and `x + c` modify the existing value of `c`.
+/// `column` is `c`, or `None` when assigning to an element of a collection, which can only be set.
+fn assignment_rhs<'a>(
+    column: Option<&'a str>,
+) -> impl Fn(Input) -> IResult<Input, (AssignmentOperator, Expr)> + 'a {
+    move |i| {
+        let same_column = |i| verify(identifier, |name: &Identifier| Some(&**name) == column)(i);
+        alt((
+            map(
+                tuple((same_column, whitespace0, tag("+"), whitespace0, expr)),
+                |(_, _, _, _, rhs)| (AssignmentOperator::Add, rhs),
+            ),
+            map(
+                tuple((same_column, whitespace0, tag("-"), whitespace0, expr)),
+                |(_, _, _, _, rhs)| (AssignmentOperator::Subtract, rhs),
+            ),
+            map(
+                tuple((expr, whitespace0, tag("+"), whitespace0, same_column)),
+                |(rhs, _, _, _, _)| (AssignmentOperator::Prepend, rhs),
+            ),
+            map(expr, |rhs| (AssignmentOperator::Set, rhs)),
+        ))(i)
+    }
+}
@@ -0,0 +1,93 @@
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map_opt, opt, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, tuple};
+use nom::IResult;
+
+use crate::ast::*;
+use crate::parser::common::{
+    if_exists, if_not_exists, properties, qualified_name, whitespace1, ws_sep_and,
+};
+use crate::parser::select::select;
+use crate::parser::table::{primary_key, table_option, TableOption};
+use crate::parser::Input;
+
+pub fn create_materialized_view(i: Input) -> IResult<Input, CreateMaterializedView> {
+    let (i, _) = tuple((
+        tag_no_case("create"),
+        whitespace1,
+        materialized_view_keyword,
+    ))(i)?;
+    cut(map_opt(
+        tuple((
+            if_not_exists,
+            whitespace1,
+            qualified_name,
+            whitespace1,
+            tag_no_case("as"),
+            whitespace1,
+            select(true),
+            whitespace1,
+            primary_key,
+            opt(preceded(
+                tuple((whitespace1, tag_no_case("with"), whitespace1)),
+                separated_list1(ws_sep_and, table_option),
+            )),
+        )),
+        |(if_not_exists, _, name, _, _, _, select, _, (partition_key, clustering_key), options)| {
+            let mut view = CreateMaterializedView {
+                if_not_exists,
+                name,
+                select,
+                partition_key,
+                clustering_key,
+                clustering_order: vec![],
+                options: vec![],
+            };
+            for option in options.unwrap_or_default() {
+                match option {
+                    TableOption::ClusteringOrder(order) => view.clustering_order = order,
+                    TableOption::CompactStorage => return None,
+                    TableOption::Property(property) => view.options.push(property),
+                }
+            }
+            Some(view)
+        },
+    ))(i)
+}
+
+pub fn alter_materialized_view(i: Input) -> IResult<Input, AlterMaterializedView> {
+    let (i, _) = tuple((tag_no_case("alter"), whitespace1, materialized_view_keyword))(i)?;
+    let (remaining_input, (if_exists, _, name, _, _, _, options)) = cut(tuple((
+        if_exists,
+        whitespace1,
+        qualified_name,
+        whitespace1,
+        tag_no_case("with"),
+        whitespace1,
+        properties,
+    )))(i)?;
+    Ok((
+        remaining_input,
+        AlterMaterializedView {
+            if_exists,
+            name,
+            options,
+        },
+    ))
+}
+
+pub fn drop_materialized_view(i: Input) -> IResult<Input, DropMaterializedView> {
+    let (i, _) = tuple((tag_no_case("drop"), whitespace1, materialized_view_keyword))(i)?;
+    let (remaining_input, (if_exists, _, name)) =
+        cut(tuple((if_exists, whitespace1, qualified_name)))(i)?;
+    Ok((remaining_input, DropMaterializedView { if_exists, name }))
+}
+
+fn materialized_view_keyword(i: Input) -> IResult<Input, Input> {
+    recognize(tuple((
+        tag_no_case("materialized"),
+        whitespace1,
+        tag_no_case("view"),
+    )))(i)
+}
@@ -0,0 +1,111 @@
+use crate::ast::*;
+
+/// Replaces every password, so the length of the original is not revealed either.
+const MASK: &str = "*****";
+
+/// Masks the string literal following every `PASSWORD` and `HASHED PASSWORD` in `query`.
+///
+/// This works on the tokens of the query rather than its AST, so it is safe to call on queries that fail to parse.
+/// String literals, quoted identifiers and `$$` code blocks are skipped over so their contents are never mistaken for the keyword.
+/// Comments are skipped like whitespace, so one between `PASSWORD` and its literal does not hide the literal.
+pub fn redact(query: &str) -> String {
+    let bytes = query.as_bytes();
+    let mut redacted = String::with_capacity(query.len());
+    let mut copied = 0;
+    let mut i = 0;
+    let mut after_password = false;
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b'\'' => {
+                i = literal_end(bytes, i, b'\'');
+                if after_password {
+                    redacted.push_str(&query[copied..start]);
+                    redacted.push('\'');
+                    redacted.push_str(MASK);
+                    redacted.push('\'');
+                    copied = i;
+                }
+                after_password = false;
+            }
+            b'"' => {
+                i = literal_end(bytes, i, b'"');
+                after_password = false;
+            }
+            b'$' if bytes[i..].starts_with(b"$$") => {
+                i = match query[i + 2..].find("$$") {
+                    Some(end) => i + 2 + end + 2,
+                    None => bytes.len(),
+                };
+                after_password = false;
+            }
+            b'-' | b'/' if bytes[i..].starts_with(b"--") || bytes[i..].starts_with(b"//") => {
+                i = query[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            b'/' if bytes[i..].starts_with(b"/*") => {
+                i = match query[i + 2..].find("*/") {
+                    Some(end) => i + 2 + end + 2,
+                    None => bytes.len(),
+                };
+            }
+            b'=' => i += 1,
+            chr if chr.is_ascii_whitespace() => i += 1,
+            chr if chr.is_ascii_alphanumeric() || chr == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                after_password = query[start..i].eq_ignore_ascii_case("password");
+            }
+            _ => {
+                i += query[i..].chars().next().unwrap().len_utf8();
+                after_password = false;
+            }
+        }
+    }
+    redacted.push_str(&query[copied..]);
+    redacted
+}
+
+/// Returns the index just past the literal starting at `start`, where `quote` is escaped by doubling it.
+fn literal_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+impl Statement {
+    /// Returns a copy of the statement with every password and hashed password masked.
+    pub fn redacted(&self) -> Statement {
+        let mut statement = self.clone();
+        match &mut statement {
+            Statement::CreateRole(CreateRole { options, .. })
+            | Statement::AlterRole(AlterRole { options, .. }) => {
+                for option in options {
+                    match option {
+                        RoleOption::Password(password) | RoleOption::HashedPassword(password) => {
+                            *password = MASK.to_string()
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Statement::CreateUser(CreateUser { password, .. })
+            | Statement::AlterUser(AlterUser { password, .. }) => {
+                if let Some(password) = password {
+                    *password = MASK.to_string();
+                }
+            }
+            _ => {}
+        }
+        statement
+    }
+}
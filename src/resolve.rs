@@ -0,0 +1,95 @@
+use crate::ast::*;
+
+/// Fills in the keyspace of every unqualified table, materialized view and index reference in `statements`,
+/// as Cassandra would when executing them in order on a single connection.
+///
+/// `keyspace` is the keyspace the connection starts in, each `USE` statement changes it for the statements that follow.
+/// References made before any keyspace is known are left unqualified.
+pub fn resolve_keyspaces(statements: &mut [Statement], keyspace: Option<&str>) {
+    let mut keyspace = keyspace.map(str::to_string);
+    for statement in statements {
+        resolve_statement(statement, &mut keyspace);
+    }
+}
+
+/// Like [`resolve_keyspaces`], for a script parsed by [`parse_script`](crate::parse_script),
+/// also filling in the keyspace of the table in each `COPY` command.
+pub fn resolve_script_keyspaces(elements: &mut [ScriptElement], keyspace: Option<&str>) {
+    let mut keyspace = keyspace.map(str::to_string);
+    for element in elements {
+        match element {
+            ScriptElement::Statement(statement) => resolve_statement(statement, &mut keyspace),
+            ScriptElement::ShellCommand(ShellCommand::Copy(copy)) => {
+                qualify(&mut copy.table, &keyspace)
+            }
+            ScriptElement::ShellCommand(_) => {}
+        }
+    }
+}
+
+fn resolve_statement(statement: &mut Statement, keyspace: &mut Option<String>) {
+    if let Statement::Use(name) = statement {
+        *keyspace = Some(name.value.clone());
+    } else {
+        for name in table_references(statement) {
+            qualify(name, keyspace);
+        }
+    }
+}
+
+fn qualify(name: &mut QualifiedName, keyspace: &Option<String>) {
+    if name.keyspace.is_none() {
+        name.keyspace = keyspace.clone().map(Identifier::from);
+    }
+}
+
+fn table_references(statement: &mut Statement) -> Vec<&mut QualifiedName> {
+    match statement {
+        Statement::Select(select) => vec![&mut select.from],
+        Statement::Insert(insert) => vec![&mut insert.table],
+        Statement::Update(update) => vec![&mut update.table],
+        Statement::Delete(delete) => vec![&mut delete.table],
+        Statement::Batch(batch) => batch
+            .statements
+            .iter_mut()
+            .map(|statement| match statement {
+                BatchStatement::Insert(insert) => &mut insert.table,
+                BatchStatement::Update(update) => &mut update.table,
+                BatchStatement::Delete(delete) => &mut delete.table,
+            })
+            .collect(),
+        Statement::CreateTable(create) => vec![&mut create.name],
+        Statement::AlterTable(alter) => vec![&mut alter.name],
+        Statement::DropTable(drop) => vec![&mut drop.name],
+        Statement::Truncate(truncate) => vec![&mut truncate.name],
+        Statement::CreateIndex(create) => vec![&mut create.table],
+        Statement::DropIndex(drop) => vec![&mut drop.name],
+        Statement::CreateMaterializedView(create) => {
+            vec![&mut create.name, &mut create.select.from]
+        }
+        Statement::AlterMaterializedView(alter) => vec![&mut alter.name],
+        Statement::DropMaterializedView(drop) => vec![&mut drop.name],
+        Statement::CreateTrigger(create) => vec![&mut create.table],
+        Statement::DropTrigger(drop) => vec![&mut drop.table],
+        Statement::GrantPermission(GrantPermission {
+            resource: Resource::Table(name),
+            ..
+        })
+        | Statement::RevokePermission(RevokePermission {
+            resource: Resource::Table(name),
+            ..
+        })
+        | Statement::ListPermissions(ListPermissions {
+            resource: Some(Resource::Table(name)),
+            ..
+        }) => vec![name],
+        Statement::Describe(Describe {
+            target:
+                DescribeTarget::Table(name)
+                | DescribeTarget::MaterializedView(name)
+                | DescribeTarget::Index(name),
+            ..
+        }) => vec![name],
+        _ => vec![],
+    }
+}
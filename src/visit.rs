@@ -0,0 +1,510 @@
+//! Traversal of a borrowed AST.
+//!
+//! Implement [`Visitor`], overriding the hooks for the nodes of interest, and pass a statement to
+//! [`Visitor::visit_statement`]. Every hook defaults to calling the matching `walk_*` function,
+//! which visits the node's children in the order they are written in CQL, so an overriding hook
+//! should call it too if it still wants the children visited.
+//!
+//! ```
+//! use cqlparser::ast::Identifier;
+//! use cqlparser::parse;
+//! use cqlparser::visit::Visitor;
+//!
+//! #[derive(Default)]
+//! struct Columns<'ast>(Vec<&'ast str>);
+//!
+//! impl<'ast> Visitor<'ast> for Columns<'ast> {
+//!     fn visit_column(&mut self, column: &'ast Identifier) {
+//!         self.0.push(column);
+//!     }
+//! }
+//!
+//! let statements = parse(
+//!     "INSERT INTO t (a, b) VALUES (1, 2);
+//!      UPDATE t SET m['k'] = 3 WHERE a = 1 AND s CONTAINS 'x'",
+//! );
+//! let mut columns = Columns::default();
+//! for statement in &statements {
+//!     columns.visit_statement(statement);
+//! }
+//! assert_eq!(columns.0, ["a", "b", "m", "a", "s"]);
+//! ```
+
+use crate::ast::*;
+
+pub trait Visitor<'ast> {
+    fn visit_statement(&mut self, statement: &'ast Statement) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_select_element(&mut self, element: &'ast SelectElement) {
+        walk_select_element(self, element)
+    }
+
+    fn visit_relation(&mut self, relation: &'ast RelationElement) {
+        walk_relation(self, relation)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_function_call(&mut self, call: &'ast FunctionCall) {
+        walk_function_call(self, call)
+    }
+
+    fn visit_qualified_name(&mut self, name: &'ast QualifiedName) {
+        walk_qualified_name(self, name)
+    }
+
+    fn visit_constant(&mut self, _constant: &'ast Constant) {}
+
+    /// Called for the name of every keyspace, table, view, index, type, field, function,
+    /// aggregate, trigger, role, property and alias, and by default for every column.
+    fn visit_identifier(&mut self, _identifier: &'ast Identifier) {}
+
+    /// Called for every column a statement defines or refers to, wherever it appears.
+    /// Defaults to calling [`Visitor::visit_identifier`].
+    fn visit_column(&mut self, column: &'ast Identifier) {
+        self.visit_identifier(column)
+    }
+
+    fn visit_type(&mut self, cql_type: &'ast CqlType) {
+        walk_type(self, cql_type)
+    }
+}
+
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    statement: &'ast Statement,
+) {
+    match statement {
+        Statement::Select(select) => walk_select(visitor, select),
+        Statement::Insert(insert) => walk_insert(visitor, insert),
+        Statement::Update(update) => walk_update(visitor, update),
+        Statement::Delete(delete) => walk_delete(visitor, delete),
+        Statement::Batch(batch) => {
+            for statement in &batch.statements {
+                match statement {
+                    BatchStatement::Insert(insert) => walk_insert(visitor, insert),
+                    BatchStatement::Update(update) => walk_update(visitor, update),
+                    BatchStatement::Delete(delete) => walk_delete(visitor, delete),
+                }
+            }
+        }
+        Statement::CreateKeyspace(create) => visitor.visit_identifier(&create.name),
+        Statement::AlterKeyspace(alter) => visitor.visit_identifier(&alter.name),
+        Statement::DropKeyspace(drop) => visitor.visit_identifier(&drop.name),
+        Statement::CreateTable(create) => {
+            visitor.visit_qualified_name(&create.name);
+            for column in &create.columns {
+                visitor.visit_column(&column.name);
+                visitor.visit_type(&column.data_type);
+            }
+            walk_columns(visitor, &create.partition_key);
+            walk_columns(visitor, &create.clustering_key);
+            for order in &create.clustering_order {
+                visitor.visit_column(&order.name);
+            }
+            walk_properties(visitor, &create.options);
+        }
+        Statement::AlterTable(alter) => {
+            visitor.visit_qualified_name(&alter.name);
+            match &alter.operation {
+                AlterTableOperation::Add(columns) => {
+                    for column in columns {
+                        visitor.visit_column(&column.name);
+                        visitor.visit_type(&column.data_type);
+                    }
+                }
+                AlterTableOperation::Drop(columns) => walk_columns(visitor, columns),
+                AlterTableOperation::Rename(renames) => {
+                    walk_renames(visitor, renames, V::visit_column)
+                }
+                AlterTableOperation::With(options) => walk_properties(visitor, options),
+            }
+        }
+        Statement::DropTable(drop) => visitor.visit_qualified_name(&drop.name),
+        Statement::Truncate(truncate) => visitor.visit_qualified_name(&truncate.name),
+        Statement::CreateIndex(create) => {
+            if let Some(name) = &create.name {
+                visitor.visit_identifier(name);
+            }
+            visitor.visit_qualified_name(&create.table);
+            for target in &create.targets {
+                match target {
+                    IndexTarget::Column(column)
+                    | IndexTarget::Keys(column)
+                    | IndexTarget::Values(column)
+                    | IndexTarget::Entries(column)
+                    | IndexTarget::Full(column) => visitor.visit_column(column),
+                }
+            }
+            walk_constant_map(visitor, &create.options);
+        }
+        Statement::DropIndex(drop) => visitor.visit_qualified_name(&drop.name),
+        Statement::CreateMaterializedView(create) => {
+            visitor.visit_qualified_name(&create.name);
+            walk_select(visitor, &create.select);
+            walk_columns(visitor, &create.partition_key);
+            walk_columns(visitor, &create.clustering_key);
+            for order in &create.clustering_order {
+                visitor.visit_column(&order.name);
+            }
+            walk_properties(visitor, &create.options);
+        }
+        Statement::AlterMaterializedView(alter) => {
+            visitor.visit_qualified_name(&alter.name);
+            walk_properties(visitor, &alter.options);
+        }
+        Statement::DropMaterializedView(drop) => visitor.visit_qualified_name(&drop.name),
+        Statement::CreateType(create) => {
+            visitor.visit_qualified_name(&create.name);
+            for field in &create.fields {
+                visitor.visit_identifier(&field.name);
+                visitor.visit_type(&field.data_type);
+            }
+        }
+        Statement::AlterType(alter) => {
+            visitor.visit_qualified_name(&alter.name);
+            match &alter.operation {
+                AlterTypeOperation::Add(field) => {
+                    visitor.visit_identifier(&field.name);
+                    visitor.visit_type(&field.data_type);
+                }
+                AlterTypeOperation::Rename(renames) => {
+                    walk_renames(visitor, renames, V::visit_identifier)
+                }
+            }
+        }
+        Statement::DropType(drop) => visitor.visit_qualified_name(&drop.name),
+        Statement::CreateFunction(create) => {
+            visitor.visit_qualified_name(&create.name);
+            for argument in &create.arguments {
+                visitor.visit_identifier(&argument.name);
+                visitor.visit_type(&argument.data_type);
+            }
+            visitor.visit_type(&create.return_type);
+            visitor.visit_constant(&create.body);
+        }
+        Statement::DropFunction(drop) => {
+            visitor.visit_qualified_name(&drop.name);
+            for argument_type in drop.argument_types.iter().flatten() {
+                visitor.visit_type(argument_type);
+            }
+        }
+        Statement::CreateAggregate(create) => {
+            visitor.visit_qualified_name(&create.name);
+            for argument_type in &create.argument_types {
+                visitor.visit_type(argument_type);
+            }
+            visitor.visit_identifier(&create.state_function);
+            visitor.visit_type(&create.state_type);
+            if let Some(final_function) = &create.final_function {
+                visitor.visit_identifier(final_function);
+            }
+            if let Some(initial_condition) = &create.initial_condition {
+                visitor.visit_expr(initial_condition);
+            }
+        }
+        Statement::DropAggregate(drop) => {
+            visitor.visit_qualified_name(&drop.name);
+            for argument_type in drop.argument_types.iter().flatten() {
+                visitor.visit_type(argument_type);
+            }
+        }
+        Statement::CreateTrigger(create) => {
+            visitor.visit_identifier(&create.name);
+            visitor.visit_qualified_name(&create.table);
+        }
+        Statement::DropTrigger(drop) => {
+            visitor.visit_identifier(&drop.name);
+            visitor.visit_qualified_name(&drop.table);
+        }
+        Statement::CreateRole(create) => {
+            visitor.visit_identifier(&create.name);
+            walk_role_options(visitor, &create.options);
+        }
+        Statement::AlterRole(alter) => {
+            visitor.visit_identifier(&alter.name);
+            walk_role_options(visitor, &alter.options);
+        }
+        Statement::DropRole(drop) => visitor.visit_identifier(&drop.name),
+        Statement::CreateUser(create) => visitor.visit_identifier(&create.name),
+        Statement::AlterUser(alter) => visitor.visit_identifier(&alter.name),
+        Statement::DropUser(drop) => visitor.visit_identifier(&drop.name),
+        Statement::GrantRole(grant) => {
+            visitor.visit_identifier(&grant.role);
+            visitor.visit_identifier(&grant.grantee);
+        }
+        Statement::RevokeRole(revoke) => {
+            visitor.visit_identifier(&revoke.role);
+            visitor.visit_identifier(&revoke.revokee);
+        }
+        Statement::GrantPermission(grant) => {
+            walk_resource(visitor, &grant.resource);
+            visitor.visit_identifier(&grant.grantee);
+        }
+        Statement::RevokePermission(revoke) => {
+            walk_resource(visitor, &revoke.resource);
+            visitor.visit_identifier(&revoke.revokee);
+        }
+        Statement::ListRoles(list) => {
+            if let Some(of) = &list.of {
+                visitor.visit_identifier(of);
+            }
+        }
+        Statement::ListPermissions(list) => {
+            if let Some(resource) = &list.resource {
+                walk_resource(visitor, resource);
+            }
+            if let Some(of) = &list.of {
+                visitor.visit_identifier(of);
+            }
+        }
+        Statement::Use(keyspace) => visitor.visit_identifier(keyspace),
+        Statement::Describe(describe) => match &describe.target {
+            DescribeTarget::Keyspace {
+                name: Some(name), ..
+            } => visitor.visit_identifier(name),
+            DescribeTarget::Table(name)
+            | DescribeTarget::Index(name)
+            | DescribeTarget::MaterializedView(name)
+            | DescribeTarget::Type(name)
+            | DescribeTarget::Function(name)
+            | DescribeTarget::Aggregate(name)
+            | DescribeTarget::Name(name) => visitor.visit_qualified_name(name),
+            _ => {}
+        },
+        Statement::ListUsers => {}
+    }
+}
+
+pub fn walk_select_element<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    element: &'ast SelectElement,
+) {
+    visitor.visit_expr(&element.expr);
+    if let Some(alias) = &element.as_alias {
+        visitor.visit_identifier(alias);
+    }
+}
+
+pub fn walk_relation<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    relation: &'ast RelationElement,
+) {
+    match relation {
+        RelationElement::Comparison(comparison) => {
+            visitor.visit_expr(&comparison.lhs);
+            visitor.visit_expr(&comparison.rhs);
+        }
+        RelationElement::In(in_) => {
+            visitor.visit_expr(&in_.lhs);
+            if let InValues::List(values) = &in_.rhs {
+                for value in values {
+                    visitor.visit_expr(value);
+                }
+            }
+        }
+        RelationElement::Contains(contains) => {
+            visitor.visit_column(&contains.lhs);
+            visitor.visit_expr(&contains.rhs);
+        }
+        RelationElement::ContainsKey(contains) => {
+            visitor.visit_column(&contains.lhs);
+            visitor.visit_expr(&contains.rhs);
+        }
+        RelationElement::IsNotNull(is_not_null) => visitor.visit_column(&is_not_null.column),
+    }
+}
+
+pub fn walk_expr<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expr) {
+    match expr {
+        Expr::Name(name) => visitor.visit_column(name),
+        Expr::Constant(constant, _) => visitor.visit_constant(constant),
+        Expr::FunctionCall(call) => visitor.visit_function_call(call),
+        Expr::Subscript(subscript) => {
+            visitor.visit_column(&subscript.column);
+            visitor.visit_expr(&subscript.key);
+        }
+        Expr::List(values, _) | Expr::Set(values, _) | Expr::Tuple(values, _) => {
+            for value in values {
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::Map(entries, _) => {
+            for (key, value) in entries {
+                visitor.visit_expr(key);
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::BindMarker(..) | Expr::Wildcard(_) => {}
+    }
+}
+
+pub fn walk_function_call<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    call: &'ast FunctionCall,
+) {
+    visitor.visit_qualified_name(&call.function);
+    for arg in &call.args {
+        visitor.visit_expr(arg);
+    }
+}
+
+pub fn walk_type<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, cql_type: &'ast CqlType) {
+    match cql_type {
+        CqlType::List(inner) | CqlType::Set(inner) | CqlType::Frozen(inner) => {
+            visitor.visit_type(inner)
+        }
+        CqlType::Map(key, value) => {
+            visitor.visit_type(key);
+            visitor.visit_type(value);
+        }
+        CqlType::Tuple(types) => {
+            for inner in types {
+                visitor.visit_type(inner);
+            }
+        }
+        CqlType::UserDefined(name) => visitor.visit_qualified_name(name),
+        CqlType::Native(_) | CqlType::Custom(_) => {}
+    }
+}
+
+pub fn walk_qualified_name<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    name: &'ast QualifiedName,
+) {
+    if let Some(keyspace) = &name.keyspace {
+        visitor.visit_identifier(keyspace);
+    }
+    visitor.visit_identifier(&name.name);
+}
+
+fn walk_select<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, select: &'ast Select) {
+    for element in &select.select {
+        visitor.visit_select_element(element);
+    }
+    visitor.visit_qualified_name(&select.from);
+    for relation in &select.where_ {
+        visitor.visit_relation(relation);
+    }
+    walk_columns(visitor, &select.group_by);
+    for order in &select.order_by {
+        visitor.visit_column(&order.name);
+    }
+}
+
+fn walk_insert<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, insert: &'ast Insert) {
+    visitor.visit_qualified_name(&insert.table);
+    walk_columns(visitor, &insert.columns);
+    for value in &insert.values {
+        visitor.visit_expr(value);
+    }
+    walk_condition(visitor, &insert.condition);
+}
+
+fn walk_update<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, update: &'ast Update) {
+    visitor.visit_qualified_name(&update.table);
+    for assignment in &update.set {
+        visitor.visit_expr(&assignment.lhs);
+        visitor.visit_expr(&assignment.rhs);
+    }
+    for relation in &update.where_ {
+        visitor.visit_relation(relation);
+    }
+    walk_condition(visitor, &update.condition);
+}
+
+fn walk_delete<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, delete: &'ast Delete) {
+    for column in &delete.columns {
+        visitor.visit_expr(column);
+    }
+    visitor.visit_qualified_name(&delete.table);
+    for relation in &delete.where_ {
+        visitor.visit_relation(relation);
+    }
+    walk_condition(visitor, &delete.condition);
+}
+
+fn walk_condition<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    condition: &'ast Option<Condition>,
+) {
+    if let Some(Condition::Columns(relations)) = condition {
+        for relation in relations {
+            visitor.visit_relation(relation);
+        }
+    }
+}
+
+fn walk_columns<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, columns: &'ast [Identifier]) {
+    for column in columns {
+        visitor.visit_column(column);
+    }
+}
+
+/// Visits both names of each rename with `visit`, which is the hook for columns or for fields.
+fn walk_renames<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    renames: &'ast [(Identifier, Identifier)],
+    visit: fn(&mut V, &'ast Identifier),
+) {
+    for (from, to) in renames {
+        visit(visitor, from);
+        visit(visitor, to);
+    }
+}
+
+fn walk_properties<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, properties: &'ast [Property]) {
+    for property in properties {
+        visitor.visit_identifier(&property.name);
+        match &property.value {
+            PropertyValue::Constant(constant) => visitor.visit_constant(constant),
+            PropertyValue::Map(entries) => walk_constant_map(visitor, entries),
+            PropertyValue::Identifier(_) => {}
+        }
+    }
+}
+
+fn walk_constant_map<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    entries: &'ast [(Constant, Constant)],
+) {
+    for (key, value) in entries {
+        visitor.visit_constant(key);
+        visitor.visit_constant(value);
+    }
+}
+
+fn walk_role_options<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    options: &'ast [RoleOption],
+) {
+    for option in options {
+        if let RoleOption::Options(entries) = option {
+            walk_constant_map(visitor, entries);
+        }
+    }
+}
+
+fn walk_resource<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, resource: &'ast Resource) {
+    match resource {
+        Resource::Keyspace(keyspace) | Resource::AllFunctionsInKeyspace(keyspace) => {
+            visitor.visit_identifier(keyspace)
+        }
+        Resource::Table(name) => visitor.visit_qualified_name(name),
+        Resource::Role(role) => visitor.visit_identifier(role),
+        Resource::Function {
+            name,
+            argument_types,
+        } => {
+            visitor.visit_qualified_name(name);
+            for argument_type in argument_types {
+                visitor.visit_type(argument_type);
+            }
+        }
+        _ => {}
+    }
+}
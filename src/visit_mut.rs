@@ -0,0 +1,499 @@
+//! In place rewriting of an AST.
+//!
+//! [`VisitorMut`] has the same hooks as [`Visitor`](crate::visit::Visitor) and visits nodes in the
+//! same order, but each hook receives a mutable reference, so it can edit a node or replace it
+//! outright, including replacing an [`Expr`] with a different variant. The `walk_*_mut` functions
+//! visit the children of a node as it is when they are called: to visit the children of a
+//! replacement, walk after replacing, and to visit the original children, walk before.
+//!
+//! ```
+//! use cqlparser::ast::{BindMarker, Expr, QualifiedName};
+//! use cqlparser::parse;
+//! use cqlparser::visit_mut::{walk_expr_mut, VisitorMut};
+//!
+//! struct Rewrite;
+//!
+//! impl VisitorMut for Rewrite {
+//!     fn visit_qualified_name_mut(&mut self, name: &mut QualifiedName) {
+//!         if name.keyspace.as_deref() == Some("staging") {
+//!             name.keyspace = Some("production".into());
+//!         }
+//!     }
+//!
+//!     fn visit_expr_mut(&mut self, expr: &mut Expr) {
+//!         if let Expr::Constant(_, span) = expr {
+//!             *expr = Expr::BindMarker(BindMarker::Anonymous, *span);
+//!         }
+//!         walk_expr_mut(self, expr);
+//!     }
+//! }
+//!
+//! let mut statements = parse("SELECT a FROM staging.t WHERE k = 1 AND c IN (2, 3)");
+//! Rewrite.visit_statement_mut(&mut statements[0]);
+//! assert_eq!(
+//!     statements[0].to_string(),
+//!     "SELECT a FROM production.t WHERE k = ? AND c IN (?, ?)"
+//! );
+//! ```
+
+use crate::ast::*;
+
+pub trait VisitorMut {
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+
+    fn visit_select_element_mut(&mut self, element: &mut SelectElement) {
+        walk_select_element_mut(self, element)
+    }
+
+    fn visit_relation_mut(&mut self, relation: &mut RelationElement) {
+        walk_relation_mut(self, relation)
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr)
+    }
+
+    fn visit_function_call_mut(&mut self, call: &mut FunctionCall) {
+        walk_function_call_mut(self, call)
+    }
+
+    fn visit_qualified_name_mut(&mut self, name: &mut QualifiedName) {
+        walk_qualified_name_mut(self, name)
+    }
+
+    fn visit_constant_mut(&mut self, _constant: &mut Constant) {}
+
+    /// Called for the same names as [`Visitor::visit_identifier`](crate::visit::Visitor::visit_identifier).
+    fn visit_identifier_mut(&mut self, _identifier: &mut Identifier) {}
+
+    /// Called for the same columns as [`Visitor::visit_column`](crate::visit::Visitor::visit_column).
+    /// Defaults to calling [`VisitorMut::visit_identifier_mut`].
+    fn visit_column_mut(&mut self, column: &mut Identifier) {
+        self.visit_identifier_mut(column)
+    }
+
+    fn visit_type_mut(&mut self, cql_type: &mut CqlType) {
+        walk_type_mut(self, cql_type)
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Select(select) => walk_select_mut(visitor, select),
+        Statement::Insert(insert) => walk_insert_mut(visitor, insert),
+        Statement::Update(update) => walk_update_mut(visitor, update),
+        Statement::Delete(delete) => walk_delete_mut(visitor, delete),
+        Statement::Batch(batch) => {
+            for statement in &mut batch.statements {
+                match statement {
+                    BatchStatement::Insert(insert) => walk_insert_mut(visitor, insert),
+                    BatchStatement::Update(update) => walk_update_mut(visitor, update),
+                    BatchStatement::Delete(delete) => walk_delete_mut(visitor, delete),
+                }
+            }
+        }
+        Statement::CreateKeyspace(create) => visitor.visit_identifier_mut(&mut create.name),
+        Statement::AlterKeyspace(alter) => visitor.visit_identifier_mut(&mut alter.name),
+        Statement::DropKeyspace(drop) => visitor.visit_identifier_mut(&mut drop.name),
+        Statement::CreateTable(create) => {
+            visitor.visit_qualified_name_mut(&mut create.name);
+            for column in &mut create.columns {
+                visitor.visit_column_mut(&mut column.name);
+                visitor.visit_type_mut(&mut column.data_type);
+            }
+            walk_columns_mut(visitor, &mut create.partition_key);
+            walk_columns_mut(visitor, &mut create.clustering_key);
+            for order in &mut create.clustering_order {
+                visitor.visit_column_mut(&mut order.name);
+            }
+            walk_properties_mut(visitor, &mut create.options);
+        }
+        Statement::AlterTable(alter) => {
+            visitor.visit_qualified_name_mut(&mut alter.name);
+            match &mut alter.operation {
+                AlterTableOperation::Add(columns) => {
+                    for column in columns {
+                        visitor.visit_column_mut(&mut column.name);
+                        visitor.visit_type_mut(&mut column.data_type);
+                    }
+                }
+                AlterTableOperation::Drop(columns) => walk_columns_mut(visitor, columns),
+                AlterTableOperation::Rename(renames) => {
+                    walk_renames_mut(visitor, renames, V::visit_column_mut)
+                }
+                AlterTableOperation::With(options) => walk_properties_mut(visitor, options),
+            }
+        }
+        Statement::DropTable(drop) => visitor.visit_qualified_name_mut(&mut drop.name),
+        Statement::Truncate(truncate) => visitor.visit_qualified_name_mut(&mut truncate.name),
+        Statement::CreateIndex(create) => {
+            if let Some(name) = &mut create.name {
+                visitor.visit_identifier_mut(name);
+            }
+            visitor.visit_qualified_name_mut(&mut create.table);
+            for target in &mut create.targets {
+                match target {
+                    IndexTarget::Column(column)
+                    | IndexTarget::Keys(column)
+                    | IndexTarget::Values(column)
+                    | IndexTarget::Entries(column)
+                    | IndexTarget::Full(column) => visitor.visit_column_mut(column),
+                }
+            }
+            walk_constant_map_mut(visitor, &mut create.options);
+        }
+        Statement::DropIndex(drop) => visitor.visit_qualified_name_mut(&mut drop.name),
+        Statement::CreateMaterializedView(create) => {
+            visitor.visit_qualified_name_mut(&mut create.name);
+            walk_select_mut(visitor, &mut create.select);
+            walk_columns_mut(visitor, &mut create.partition_key);
+            walk_columns_mut(visitor, &mut create.clustering_key);
+            for order in &mut create.clustering_order {
+                visitor.visit_column_mut(&mut order.name);
+            }
+            walk_properties_mut(visitor, &mut create.options);
+        }
+        Statement::AlterMaterializedView(alter) => {
+            visitor.visit_qualified_name_mut(&mut alter.name);
+            walk_properties_mut(visitor, &mut alter.options);
+        }
+        Statement::DropMaterializedView(drop) => visitor.visit_qualified_name_mut(&mut drop.name),
+        Statement::CreateType(create) => {
+            visitor.visit_qualified_name_mut(&mut create.name);
+            for field in &mut create.fields {
+                visitor.visit_identifier_mut(&mut field.name);
+                visitor.visit_type_mut(&mut field.data_type);
+            }
+        }
+        Statement::AlterType(alter) => {
+            visitor.visit_qualified_name_mut(&mut alter.name);
+            match &mut alter.operation {
+                AlterTypeOperation::Add(field) => {
+                    visitor.visit_identifier_mut(&mut field.name);
+                    visitor.visit_type_mut(&mut field.data_type);
+                }
+                AlterTypeOperation::Rename(renames) => {
+                    walk_renames_mut(visitor, renames, V::visit_identifier_mut)
+                }
+            }
+        }
+        Statement::DropType(drop) => visitor.visit_qualified_name_mut(&mut drop.name),
+        Statement::CreateFunction(create) => {
+            visitor.visit_qualified_name_mut(&mut create.name);
+            for argument in &mut create.arguments {
+                visitor.visit_identifier_mut(&mut argument.name);
+                visitor.visit_type_mut(&mut argument.data_type);
+            }
+            visitor.visit_type_mut(&mut create.return_type);
+            visitor.visit_constant_mut(&mut create.body);
+        }
+        Statement::DropFunction(drop) => {
+            visitor.visit_qualified_name_mut(&mut drop.name);
+            for argument_type in drop.argument_types.iter_mut().flatten() {
+                visitor.visit_type_mut(argument_type);
+            }
+        }
+        Statement::CreateAggregate(create) => {
+            visitor.visit_qualified_name_mut(&mut create.name);
+            for argument_type in &mut create.argument_types {
+                visitor.visit_type_mut(argument_type);
+            }
+            visitor.visit_identifier_mut(&mut create.state_function);
+            visitor.visit_type_mut(&mut create.state_type);
+            if let Some(final_function) = &mut create.final_function {
+                visitor.visit_identifier_mut(final_function);
+            }
+            if let Some(initial_condition) = &mut create.initial_condition {
+                visitor.visit_expr_mut(initial_condition);
+            }
+        }
+        Statement::DropAggregate(drop) => {
+            visitor.visit_qualified_name_mut(&mut drop.name);
+            for argument_type in drop.argument_types.iter_mut().flatten() {
+                visitor.visit_type_mut(argument_type);
+            }
+        }
+        Statement::CreateTrigger(create) => {
+            visitor.visit_identifier_mut(&mut create.name);
+            visitor.visit_qualified_name_mut(&mut create.table);
+        }
+        Statement::DropTrigger(drop) => {
+            visitor.visit_identifier_mut(&mut drop.name);
+            visitor.visit_qualified_name_mut(&mut drop.table);
+        }
+        Statement::CreateRole(create) => {
+            visitor.visit_identifier_mut(&mut create.name);
+            walk_role_options_mut(visitor, &mut create.options);
+        }
+        Statement::AlterRole(alter) => {
+            visitor.visit_identifier_mut(&mut alter.name);
+            walk_role_options_mut(visitor, &mut alter.options);
+        }
+        Statement::DropRole(drop) => visitor.visit_identifier_mut(&mut drop.name),
+        Statement::CreateUser(create) => visitor.visit_identifier_mut(&mut create.name),
+        Statement::AlterUser(alter) => visitor.visit_identifier_mut(&mut alter.name),
+        Statement::DropUser(drop) => visitor.visit_identifier_mut(&mut drop.name),
+        Statement::GrantRole(grant) => {
+            visitor.visit_identifier_mut(&mut grant.role);
+            visitor.visit_identifier_mut(&mut grant.grantee);
+        }
+        Statement::RevokeRole(revoke) => {
+            visitor.visit_identifier_mut(&mut revoke.role);
+            visitor.visit_identifier_mut(&mut revoke.revokee);
+        }
+        Statement::GrantPermission(grant) => {
+            walk_resource_mut(visitor, &mut grant.resource);
+            visitor.visit_identifier_mut(&mut grant.grantee);
+        }
+        Statement::RevokePermission(revoke) => {
+            walk_resource_mut(visitor, &mut revoke.resource);
+            visitor.visit_identifier_mut(&mut revoke.revokee);
+        }
+        Statement::ListRoles(list) => {
+            if let Some(of) = &mut list.of {
+                visitor.visit_identifier_mut(of);
+            }
+        }
+        Statement::ListPermissions(list) => {
+            if let Some(resource) = &mut list.resource {
+                walk_resource_mut(visitor, resource);
+            }
+            if let Some(of) = &mut list.of {
+                visitor.visit_identifier_mut(of);
+            }
+        }
+        Statement::Use(keyspace) => visitor.visit_identifier_mut(keyspace),
+        Statement::Describe(describe) => match &mut describe.target {
+            DescribeTarget::Keyspace {
+                name: Some(name), ..
+            } => visitor.visit_identifier_mut(name),
+            DescribeTarget::Table(name)
+            | DescribeTarget::Index(name)
+            | DescribeTarget::MaterializedView(name)
+            | DescribeTarget::Type(name)
+            | DescribeTarget::Function(name)
+            | DescribeTarget::Aggregate(name)
+            | DescribeTarget::Name(name) => visitor.visit_qualified_name_mut(name),
+            _ => {}
+        },
+        Statement::ListUsers => {}
+    }
+}
+
+pub fn walk_select_element_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    element: &mut SelectElement,
+) {
+    visitor.visit_expr_mut(&mut element.expr);
+    if let Some(alias) = &mut element.as_alias {
+        visitor.visit_identifier_mut(alias);
+    }
+}
+
+pub fn walk_relation_mut<V: VisitorMut + ?Sized>(visitor: &mut V, relation: &mut RelationElement) {
+    match relation {
+        RelationElement::Comparison(comparison) => {
+            visitor.visit_expr_mut(&mut comparison.lhs);
+            visitor.visit_expr_mut(&mut comparison.rhs);
+        }
+        RelationElement::In(in_) => {
+            visitor.visit_expr_mut(&mut in_.lhs);
+            if let InValues::List(values) = &mut in_.rhs {
+                for value in values {
+                    visitor.visit_expr_mut(value);
+                }
+            }
+        }
+        RelationElement::Contains(contains) => {
+            visitor.visit_column_mut(&mut contains.lhs);
+            visitor.visit_expr_mut(&mut contains.rhs);
+        }
+        RelationElement::ContainsKey(contains) => {
+            visitor.visit_column_mut(&mut contains.lhs);
+            visitor.visit_expr_mut(&mut contains.rhs);
+        }
+        RelationElement::IsNotNull(is_not_null) => {
+            visitor.visit_column_mut(&mut is_not_null.column)
+        }
+    }
+}
+
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Name(name) => visitor.visit_column_mut(name),
+        Expr::Constant(constant, _) => visitor.visit_constant_mut(constant),
+        Expr::FunctionCall(call) => visitor.visit_function_call_mut(call),
+        Expr::Subscript(subscript) => {
+            visitor.visit_column_mut(&mut subscript.column);
+            visitor.visit_expr_mut(&mut subscript.key);
+        }
+        Expr::List(values, _) | Expr::Set(values, _) | Expr::Tuple(values, _) => {
+            for value in values {
+                visitor.visit_expr_mut(value);
+            }
+        }
+        Expr::Map(entries, _) => {
+            for (key, value) in entries {
+                visitor.visit_expr_mut(key);
+                visitor.visit_expr_mut(value);
+            }
+        }
+        Expr::BindMarker(..) | Expr::Wildcard(_) => {}
+    }
+}
+
+pub fn walk_function_call_mut<V: VisitorMut + ?Sized>(visitor: &mut V, call: &mut FunctionCall) {
+    visitor.visit_qualified_name_mut(&mut call.function);
+    for arg in &mut call.args {
+        visitor.visit_expr_mut(arg);
+    }
+}
+
+pub fn walk_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, cql_type: &mut CqlType) {
+    match cql_type {
+        CqlType::List(inner) | CqlType::Set(inner) | CqlType::Frozen(inner) => {
+            visitor.visit_type_mut(inner)
+        }
+        CqlType::Map(key, value) => {
+            visitor.visit_type_mut(key);
+            visitor.visit_type_mut(value);
+        }
+        CqlType::Tuple(types) => {
+            for inner in types {
+                visitor.visit_type_mut(inner);
+            }
+        }
+        CqlType::UserDefined(name) => visitor.visit_qualified_name_mut(name),
+        CqlType::Native(_) | CqlType::Custom(_) => {}
+    }
+}
+
+pub fn walk_qualified_name_mut<V: VisitorMut + ?Sized>(visitor: &mut V, name: &mut QualifiedName) {
+    if let Some(keyspace) = &mut name.keyspace {
+        visitor.visit_identifier_mut(keyspace);
+    }
+    visitor.visit_identifier_mut(&mut name.name);
+}
+
+fn walk_select_mut<V: VisitorMut + ?Sized>(visitor: &mut V, select: &mut Select) {
+    for element in &mut select.select {
+        visitor.visit_select_element_mut(element);
+    }
+    visitor.visit_qualified_name_mut(&mut select.from);
+    for relation in &mut select.where_ {
+        visitor.visit_relation_mut(relation);
+    }
+    walk_columns_mut(visitor, &mut select.group_by);
+    for order in &mut select.order_by {
+        visitor.visit_column_mut(&mut order.name);
+    }
+}
+
+fn walk_insert_mut<V: VisitorMut + ?Sized>(visitor: &mut V, insert: &mut Insert) {
+    visitor.visit_qualified_name_mut(&mut insert.table);
+    walk_columns_mut(visitor, &mut insert.columns);
+    for value in &mut insert.values {
+        visitor.visit_expr_mut(value);
+    }
+    walk_condition_mut(visitor, &mut insert.condition);
+}
+
+fn walk_update_mut<V: VisitorMut + ?Sized>(visitor: &mut V, update: &mut Update) {
+    visitor.visit_qualified_name_mut(&mut update.table);
+    for assignment in &mut update.set {
+        visitor.visit_expr_mut(&mut assignment.lhs);
+        visitor.visit_expr_mut(&mut assignment.rhs);
+    }
+    for relation in &mut update.where_ {
+        visitor.visit_relation_mut(relation);
+    }
+    walk_condition_mut(visitor, &mut update.condition);
+}
+
+fn walk_delete_mut<V: VisitorMut + ?Sized>(visitor: &mut V, delete: &mut Delete) {
+    for column in &mut delete.columns {
+        visitor.visit_expr_mut(column);
+    }
+    visitor.visit_qualified_name_mut(&mut delete.table);
+    for relation in &mut delete.where_ {
+        visitor.visit_relation_mut(relation);
+    }
+    walk_condition_mut(visitor, &mut delete.condition);
+}
+
+fn walk_condition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, condition: &mut Option<Condition>) {
+    if let Some(Condition::Columns(relations)) = condition {
+        for relation in relations {
+            visitor.visit_relation_mut(relation);
+        }
+    }
+}
+
+fn walk_columns_mut<V: VisitorMut + ?Sized>(visitor: &mut V, columns: &mut [Identifier]) {
+    for column in columns {
+        visitor.visit_column_mut(column);
+    }
+}
+
+/// Visits both names of each rename with `visit`, which is the hook for columns or for fields.
+fn walk_renames_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    renames: &mut [(Identifier, Identifier)],
+    visit: fn(&mut V, &mut Identifier),
+) {
+    for (from, to) in renames {
+        visit(visitor, from);
+        visit(visitor, to);
+    }
+}
+
+fn walk_properties_mut<V: VisitorMut + ?Sized>(visitor: &mut V, properties: &mut [Property]) {
+    for property in properties {
+        visitor.visit_identifier_mut(&mut property.name);
+        match &mut property.value {
+            PropertyValue::Constant(constant) => visitor.visit_constant_mut(constant),
+            PropertyValue::Map(entries) => walk_constant_map_mut(visitor, entries),
+            PropertyValue::Identifier(_) => {}
+        }
+    }
+}
+
+fn walk_constant_map_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    entries: &mut [(Constant, Constant)],
+) {
+    for (key, value) in entries {
+        visitor.visit_constant_mut(key);
+        visitor.visit_constant_mut(value);
+    }
+}
+
+fn walk_role_options_mut<V: VisitorMut + ?Sized>(visitor: &mut V, options: &mut [RoleOption]) {
+    for option in options {
+        if let RoleOption::Options(entries) = option {
+            walk_constant_map_mut(visitor, entries);
+        }
+    }
+}
+
+fn walk_resource_mut<V: VisitorMut + ?Sized>(visitor: &mut V, resource: &mut Resource) {
+    match resource {
+        Resource::Keyspace(keyspace) | Resource::AllFunctionsInKeyspace(keyspace) => {
+            visitor.visit_identifier_mut(keyspace)
+        }
+        Resource::Table(name) => visitor.visit_qualified_name_mut(name),
+        Resource::Role(role) => visitor.visit_identifier_mut(role),
+        Resource::Function {
+            name,
+            argument_types,
+        } => {
+            visitor.visit_qualified_name_mut(name);
+            for argument_type in argument_types {
+                visitor.visit_type_mut(argument_type);
+            }
+        }
+        _ => {}
+    }
+}
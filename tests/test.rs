@@ -1,15 +1,350 @@
+use std::fmt::Debug;
+
 use cqlparser::ast::*;
-use cqlparser::parse;
+use cqlparser::visit::{
+    walk_expr, walk_function_call, walk_qualified_name, walk_relation, walk_select_element,
+    walk_statement, walk_type, Visitor,
+};
+use cqlparser::visit_mut::{
+    walk_expr_mut, walk_function_call_mut, walk_qualified_name_mut, walk_relation_mut,
+    walk_select_element_mut, walk_statement_mut, walk_type_mut, VisitorMut,
+};
+use cqlparser::{
+    fingerprint, format_script, format_statement, parse, parse_script, parse_with,
+    parse_with_spans, redact, resolve_keyspaces, resolve_script_keyspaces, FormatOptions,
+    KeywordCase, ParseError, ParseOptions,
+};
 
 fn assert_parses(input: &[&str], ast: Vec<Statement>) {
     for input in input {
-        assert_eq!(parse(input), ast);
+        assert_eq_ignoring_spans(&parse(input), &ast);
     }
 }
 
+/// Asserts the nodes are equal apart from their spans,
+/// so nodes built by hand with `Span::default()` can be compared with parsed ones.
+#[track_caller]
+fn assert_eq_ignoring_spans(left: &impl Debug, right: &impl Debug) {
+    let (left, right) = (strip_spans(left), strip_spans(right));
+    assert!(left == right, "left:\n{}\nright:\n{}", left, right);
+}
+
+/// Renders `node` with the offsets of every span set to 0.
+fn strip_spans(node: &impl Debug) -> String {
+    format!("{:#?}", node)
+        .lines()
+        .map(|line| {
+            let field = line.trim_start();
+            let indent = &line[..line.len() - field.len()];
+            match field.split_once(": ") {
+                Some((name @ ("start" | "end"), offset))
+                    if offset.trim_end_matches(',').parse::<usize>().is_ok() =>
+                {
+                    format!("{}{}: 0,\n", indent, name)
+                }
+                _ => format!("{}\n", line),
+            }
+        })
+        .collect()
+}
+
 #[test]
 fn test_insert() {
-    assert_parses(&["insert"], vec![Statement::Insert(Insert {})]);
+    assert_parses(
+        &[
+            "insert into ks.table (pk, field) values (1, ?)",
+            "INSERT   INTO   ks.table(pk,field)VALUES(1,?)",
+        ],
+        vec![Statement::Insert(Insert {
+            table: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+            columns: vec!["pk".into(), "field".into()],
+            values: vec![
+                Expr::Constant(Constant::Decimal(1), Span::default()),
+                Expr::BindMarker(BindMarker::Anonymous, Span::default()),
+            ],
+            condition: None,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_insert_if_not_exists() {
+    assert_parses(
+        &["INSERT INTO table (pk, tags) VALUES (:pk, {'a', 'b'}) IF NOT EXISTS"],
+        vec![Statement::Insert(Insert {
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            columns: vec!["pk".into(), "tags".into()],
+            values: vec![
+                Expr::BindMarker(BindMarker::Named("pk".to_string()), Span::default()),
+                Expr::Set(
+                    vec![
+                        Expr::Constant(Constant::String("a".to_string()), Span::default()),
+                        Expr::Constant(Constant::String("b".to_string()), Span::default()),
+                    ],
+                    Span::default(),
+                ),
+            ],
+            condition: Some(Condition::NotExists),
+            using: None,
+        })],
+    );
+    assert!(parse("INSERT INTO table (pk) VALUES (1) IF NOT EXISTS")[0].is_lwt());
+    assert!(!parse("INSERT INTO table (pk) VALUES (1)")[0].is_lwt());
+}
+
+#[test]
+fn test_update() {
+    assert_parses(
+        &[
+            "update table set a = 1, l = l + [2], l = [0] + l, m['k'] = 'v', c -= 3 where pk = 1 and ck in (1, 2)",
+            "UPDATE table SET a=1,l=l+[2],l=[0]+l,m[ 'k' ]='v',c-=3 WHERE pk=1 AND ck IN(1,2)",
+        ],
+        vec![Statement::Update(Update {
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            using: None,
+            set: vec![
+                Assignment {
+                    lhs: Expr::Name("a".into()),
+                    operator: AssignmentOperator::Set,
+                    rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                    span: Span::default(),
+                },
+                Assignment {
+                    lhs: Expr::Name("l".into()),
+                    operator: AssignmentOperator::Add,
+                    rhs: Expr::List(vec![Expr::Constant(Constant::Decimal(2), Span::default())], Span::default()),
+                    span: Span::default(),
+                },
+                Assignment {
+                    lhs: Expr::Name("l".into()),
+                    operator: AssignmentOperator::Prepend,
+                    rhs: Expr::List(vec![Expr::Constant(Constant::Decimal(0), Span::default())], Span::default()),
+                    span: Span::default(),
+                },
+                Assignment {
+                    lhs: Expr::Subscript(Subscript {
+                        column: "m".into(),
+                        key: Box::new(Expr::Constant(Constant::String("k".to_string()), Span::default())),
+                        span: Span::default(),
+                    }),
+                    operator: AssignmentOperator::Set,
+                    rhs: Expr::Constant(Constant::String("v".to_string()), Span::default()),
+                    span: Span::default(),
+                },
+                Assignment {
+                    lhs: Expr::Name("c".into()),
+                    operator: AssignmentOperator::Subtract,
+                    rhs: Expr::Constant(Constant::Decimal(3), Span::default()),
+                    span: Span::default(),
+                },
+            ],
+            where_: vec![
+                RelationElement::Comparison(RelationComparison {
+                    lhs: Expr::Name("pk".into()),
+                    operator: ComparisonOperator::Equals,
+                    rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                    span: Span::default(),
+                }),
+                RelationElement::In(RelationIn {
+                    lhs: Expr::Name("ck".into()),
+                    rhs: InValues::List(vec![
+                        Expr::Constant(Constant::Decimal(1), Span::default()),
+                        Expr::Constant(Constant::Decimal(2), Span::default()),
+                    ]),
+                    span: Span::default(),
+                }),
+            ],
+            condition: None,
+        })],
+    );
+}
+
+#[test]
+fn test_update_modifies_other_column() {
+    // `a = b + 1` would need a read of `b`, which CQL cannot express
+    for query in [
+        "UPDATE t SET a = b + 1 WHERE k = 0",
+        "UPDATE t SET a = b - 1 WHERE k = 0",
+        "UPDATE t SET l = [0] + m WHERE k = 0",
+    ] {
+        assert!(
+            matches!(
+                parse_with(query, ParseOptions::default()),
+                Err(ParseError::Syntax { .. })
+            ),
+            "{}",
+            query
+        );
+    }
+}
+
+#[test]
+fn test_update_if_conditions() {
+    assert_parses(
+        &["UPDATE ks.table SET a = 1 WHERE pk = 1 IF b = 2 AND m['k'] != 'v' AND c IN (3, 4)"],
+        vec![Statement::Update(Update {
+            table: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+            using: None,
+            set: vec![Assignment {
+                lhs: Expr::Name("a".into()),
+                operator: AssignmentOperator::Set,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            }],
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("pk".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            })],
+            condition: Some(Condition::Columns(vec![
+                RelationElement::Comparison(RelationComparison {
+                    lhs: Expr::Name("b".into()),
+                    operator: ComparisonOperator::Equals,
+                    rhs: Expr::Constant(Constant::Decimal(2), Span::default()),
+                    span: Span::default(),
+                }),
+                RelationElement::Comparison(RelationComparison {
+                    lhs: Expr::Subscript(Subscript {
+                        column: "m".into(),
+                        key: Box::new(Expr::Constant(
+                            Constant::String("k".to_string()),
+                            Span::default(),
+                        )),
+                        span: Span::default(),
+                    }),
+                    operator: ComparisonOperator::NotEquals,
+                    rhs: Expr::Constant(Constant::String("v".to_string()), Span::default()),
+                    span: Span::default(),
+                }),
+                RelationElement::In(RelationIn {
+                    lhs: Expr::Name("c".into()),
+                    rhs: InValues::List(vec![
+                        Expr::Constant(Constant::Decimal(3), Span::default()),
+                        Expr::Constant(Constant::Decimal(4), Span::default()),
+                    ]),
+                    span: Span::default(),
+                }),
+            ])),
+        })],
+    );
+    assert!(parse("UPDATE table SET a = 1 WHERE pk = 1 IF EXISTS")[0].is_lwt());
+    assert!(!parse("UPDATE table SET a = 1 WHERE pk = 1")[0].is_lwt());
+}
+
+#[test]
+fn test_relation_bind_markers() {
+    let query =
+        "SELECT * FROM t WHERE k IN ? AND c IN :cs AND s CONTAINS ? AND m CONTAINS KEY :key";
+    let statement = parse(query).remove(0);
+    let select = match &statement {
+        Statement::Select(select) => select,
+        statement => panic!("expected a select, got {:?}", statement),
+    };
+    assert_eq_ignoring_spans(
+        &select.where_,
+        &[
+            RelationElement::In(RelationIn {
+                lhs: Expr::Name("k".into()),
+                rhs: InValues::BindMarker(BindMarker::Anonymous, Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::In(RelationIn {
+                lhs: Expr::Name("c".into()),
+                rhs: InValues::BindMarker(BindMarker::Named("cs".to_string()), Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::Contains(RelationContains {
+                lhs: "s".into(),
+                rhs: Expr::BindMarker(BindMarker::Anonymous, Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::ContainsKey(RelationContainsKey {
+                lhs: "m".into(),
+                rhs: Expr::BindMarker(BindMarker::Named("key".to_string()), Span::default()),
+                span: Span::default(),
+            }),
+        ],
+    );
+    assert_eq!(statement.to_string(), query);
+}
+
+#[test]
+fn test_delete() {
+    assert_parses(
+        &[
+            "delete from table where pk = 1",
+            "DELETE   FROM   table   WHERE   pk   =   1",
+        ],
+        vec![Statement::Delete(Delete {
+            columns: vec![],
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            using: None,
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("pk".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            })],
+            condition: None,
+        })],
+    );
+}
+
+#[test]
+fn test_delete_columns_if_exists() {
+    assert_parses(
+        &["DELETE a, m['k'] FROM ks.table WHERE pk = ? IF EXISTS"],
+        vec![Statement::Delete(Delete {
+            columns: vec![
+                Expr::Name("a".into()),
+                Expr::Subscript(Subscript {
+                    column: "m".into(),
+                    key: Box::new(Expr::Constant(
+                        Constant::String("k".to_string()),
+                        Span::default(),
+                    )),
+                    span: Span::default(),
+                }),
+            ],
+            table: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+            using: None,
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("pk".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::BindMarker(BindMarker::Anonymous, Span::default()),
+                span: Span::default(),
+            })],
+            condition: Some(Condition::Exists),
+        })],
+    );
+    assert!(parse("DELETE FROM table WHERE pk = 1 IF a = 1")[0].is_lwt());
+    assert!(!parse("SELECT * FROM table WHERE pk = 1")[0].is_lwt());
 }
 
 #[test]
@@ -23,14 +358,22 @@ fn test_select_one_field() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -46,14 +389,22 @@ fn test_select_one_field_as() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: Some("alias".into()),
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -70,23 +421,33 @@ fn test_select_many_fields_as() {
             json: false,
             select: vec![
                 SelectElement {
-                    expr: Expr::Name("field1".to_string()),
+                    expr: Expr::Name("field1".into()),
                     as_alias: Some("foo".into()),
+                    span: Span::default(),
                 },
                 SelectElement {
-                    expr: Expr::Name("field2".to_string()),
+                    expr: Expr::Name("field2".into()),
                     as_alias: None,
+                    span: Span::default(),
                 },
                 SelectElement {
-                    expr: Expr::Name("field3".to_string()),
+                    expr: Expr::Name("field3".into()),
                     as_alias: Some("bar".into()),
+                    span: Span::default(),
                 },
             ],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -102,14 +463,22 @@ fn test_select_distinct() {
             distinct: true,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -125,14 +494,22 @@ fn test_select_json() {
             distinct: false,
             json: true,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -150,17 +527,26 @@ fn test_select_order_by_asc() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: Some(OrderBy {
-                name: "pk_field".to_string(),
+            group_by: vec![],
+            order_by: vec![OrderBy {
+                name: "pk_field".into(),
                 ordering: Ordering::Asc,
-            }),
+                span: Span::default(),
+            }],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -176,18 +562,27 @@ fn test_select_where_field_greater_than_int() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![RelationElement::Comparison(RelationComparison {
-                lhs: Expr::Name("foo".to_string()),
+                lhs: Expr::Name("foo".into()),
                 operator: ComparisonOperator::GreaterThan,
-                rhs: Expr::Constant(Constant::Decimal(1)),
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
             })],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -203,25 +598,35 @@ fn test_select_where_field_and() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![
                 RelationElement::Comparison(RelationComparison {
-                    lhs: Expr::Name("foo".to_string()),
+                    lhs: Expr::Name("foo".into()),
                     operator: ComparisonOperator::LessThan,
-                    rhs: Expr::Constant(Constant::Decimal(1)),
+                    rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                    span: Span::default(),
                 }),
                 RelationElement::Comparison(RelationComparison {
-                    lhs: Expr::Name("bar".to_string()),
+                    lhs: Expr::Name("bar".into()),
                     operator: ComparisonOperator::LessThanOrEqualTo,
-                    rhs: Expr::Constant(Constant::Decimal(1111)),
+                    rhs: Expr::Constant(Constant::Decimal(1111), Span::default()),
+                    span: Span::default(),
                 }),
             ],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -237,18 +642,27 @@ fn test_select_where_field_greater_than_or_equal_negative_int() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![RelationElement::Comparison(RelationComparison {
-                lhs: Expr::Name("foo".to_string()),
+                lhs: Expr::Name("foo".into()),
                 operator: ComparisonOperator::GreaterThanOrEqualTo,
-                rhs: Expr::Constant(Constant::Decimal(-13)),
+                rhs: Expr::Constant(Constant::Decimal(-13), Span::default()),
+                span: Span::default(),
             })],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -264,18 +678,27 @@ fn test_select_where_field_equals_bool() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![RelationElement::Comparison(RelationComparison {
-                lhs: Expr::Name("foo".to_string()),
+                lhs: Expr::Name("foo".into()),
                 operator: ComparisonOperator::Equals,
-                rhs: Expr::Constant(Constant::Bool(true)),
+                rhs: Expr::Constant(Constant::Bool(true), Span::default()),
+                span: Span::default(),
             })],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -291,18 +714,27 @@ fn test_select_where_field_equals_string() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![RelationElement::Comparison(RelationComparison {
-                lhs: Expr::Name("foo".to_string()),
+                lhs: Expr::Name("foo".into()),
                 operator: ComparisonOperator::Equals,
-                rhs: Expr::Constant(Constant::String("bar".into())),
+                rhs: Expr::Constant(Constant::String("bar".into()), Span::default()),
+                span: Span::default(),
             })],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -318,18 +750,30 @@ fn test_select_where_field_equals_string_escape_quote() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![RelationElement::Comparison(RelationComparison {
-                lhs: Expr::Name("foo".to_string()),
+                lhs: Expr::Name("foo".into()),
                 operator: ComparisonOperator::Equals,
-                rhs: Expr::Constant(Constant::String("lucas' cool string ''".into())),
+                rhs: Expr::Constant(
+                    Constant::String("lucas' cool string ''".into()),
+                    Span::default(),
+                ),
+                span: Span::default(),
             })],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -345,17 +789,26 @@ fn test_select_order_by_desc() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: Some(OrderBy {
-                name: "foo".to_string(),
+            group_by: vec![],
+            order_by: vec![OrderBy {
+                name: "foo".into(),
                 ordering: Ordering::Desc,
-            }),
+                span: Span::default(),
+            }],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -371,14 +824,22 @@ fn test_select_limit_42() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
-            limit: Some(42),
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: Some(Limit::Literal(42)),
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -394,14 +855,22 @@ fn test_select_limit_0() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
-            limit: Some(0),
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: Some(Limit::Literal(0)),
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -417,14 +886,22 @@ fn test_select_allow_filtering() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Name("field".to_string()),
+                expr: Expr::Name("field".into()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: true,
+            using: None,
         })],
     );
 }
@@ -442,19 +919,28 @@ fn test_select_two_fields() {
             json: false,
             select: vec![
                 SelectElement {
-                    expr: Expr::Name("field1".to_string()),
+                    expr: Expr::Name("field1".into()),
                     as_alias: None,
+                    span: Span::default(),
                 },
                 SelectElement {
-                    expr: Expr::Name("field2".to_string()),
+                    expr: Expr::Name("field2".into()),
                     as_alias: None,
+                    span: Span::default(),
                 },
             ],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -470,14 +956,22 @@ fn test_select_all() {
             distinct: false,
             json: false,
             select: vec![SelectElement {
-                expr: Expr::Wildcard,
+                expr: Expr::Wildcard(Span::default()),
                 as_alias: None,
+                span: Span::default(),
             }],
-            from: vec!["foo".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "foo".into(),
+                span: Span::default(),
+            },
             where_: vec![],
-            order_by: None,
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
             limit: None,
             allow_filtering: false,
+            using: None,
         })],
     );
 }
@@ -491,26 +985,3113 @@ fn test_select_christmas_tree() {
             json: true,
             select: vec![
                 SelectElement {
-                    expr: Expr::Name("field1".to_string()),
+                    expr: Expr::Name("field1".into()),
                     as_alias: None,
+                    span: Span::default(),
                 },
                 SelectElement {
-                    expr: Expr::Name("field2".to_string()),
+                    expr: Expr::Name("field2".into()),
                     as_alias: Some("foo".into()),
+                    span: Span::default(),
                 },
             ],
-            from: vec!["table".to_string()],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
             where_: vec![RelationElement::Comparison(RelationComparison {
-                lhs: Expr::Name("foo".to_string()),
+                lhs: Expr::Name("foo".into()),
                 operator: ComparisonOperator::Equals,
-                rhs: Expr::Constant(Constant::Decimal(1)),
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
             })],
-            order_by: Some(OrderBy {
-                name: "order_column".to_string(),
+            group_by: vec![],
+            order_by: vec![OrderBy {
+                name: "order_column".into(),
                 ordering: Ordering::Desc,
-            }),
-            limit: Some(9999),
+                span: Span::default(),
+            }],
+            per_partition_limit: None,
+            limit: Some(Limit::Literal(9999)),
+            allow_filtering: true,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_group_by() {
+    assert_parses(
+        &[
+            "SELECT pk, count FROM table group by pk, ck",
+            "SELECT pk,count FROM table   GROUP   BY   pk  ,  ck",
+        ],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![
+                SelectElement {
+                    expr: Expr::Name("pk".into()),
+                    as_alias: None,
+                    span: Span::default(),
+                },
+                SelectElement {
+                    expr: Expr::Name("count".into()),
+                    as_alias: None,
+                    span: Span::default(),
+                },
+            ],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec!["pk".into(), "ck".into()],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_order_by_many() {
+    assert_parses(
+        &[
+            "SELECT field FROM table order by c1 asc, c2 desc, c3",
+            "SELECT field FROM table ORDER BY c1 ASC,c2 DESC  ,  c3",
+        ],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![SelectElement {
+                expr: Expr::Name("field".into()),
+                as_alias: None,
+                span: Span::default(),
+            }],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![
+                OrderBy {
+                    name: "c1".into(),
+                    ordering: Ordering::Asc,
+                    span: Span::default(),
+                },
+                OrderBy {
+                    name: "c2".into(),
+                    ordering: Ordering::Desc,
+                    span: Span::default(),
+                },
+                OrderBy {
+                    name: "c3".into(),
+                    ordering: Ordering::Asc,
+                    span: Span::default(),
+                },
+            ],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_per_partition_limit() {
+    assert_parses(
+        &[
+            "SELECT field FROM table per partition limit 2 limit 10",
+            "SELECT field FROM table   PER   PARTITION   LIMIT   2   LIMIT   10",
+        ],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![SelectElement {
+                expr: Expr::Name("field".into()),
+                as_alias: None,
+                span: Span::default(),
+            }],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: Some(Limit::Literal(2)),
+            limit: Some(Limit::Literal(10)),
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_limit_bind_markers() {
+    assert_parses(
+        &["SELECT field FROM table PER PARTITION LIMIT ? LIMIT :max_rows"],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![SelectElement {
+                expr: Expr::Name("field".into()),
+                as_alias: None,
+                span: Span::default(),
+            }],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: Some(Limit::BindMarker(BindMarker::Anonymous)),
+            limit: Some(Limit::BindMarker(BindMarker::Named("max_rows".to_string()))),
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_strict_christmas_tree() {
+    assert_eq_ignoring_spans(
+        &parse_with(
+            "SELECT JSON DISTINCT field1, field2 AS foo FROM table WHERE foo = 1 AND bar = 2 GROUP BY pk ORDER BY order_column DESC PER PARTITION LIMIT 1 LIMIT 9999 ALLOW FILTERING",
+            ParseOptions { strict: true, ..ParseOptions::default() },
+        )
+        .unwrap(),
+        &vec![Statement::Select(Select {
+            distinct: true,
+            json: true,
+            select: vec![
+                SelectElement {
+                    expr: Expr::Name("field1".into()),
+                    as_alias: None,
+                    span: Span::default(),
+                },
+                SelectElement {
+                    expr: Expr::Name("field2".into()),
+                    as_alias: Some("foo".into()),
+                    span: Span::default(),
+                },
+            ],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![
+                RelationElement::Comparison(RelationComparison {
+                    lhs: Expr::Name("foo".into()),
+                    operator: ComparisonOperator::Equals,
+                    rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                    span: Span::default(),
+                }),
+                RelationElement::Comparison(RelationComparison {
+                    lhs: Expr::Name("bar".into()),
+                    operator: ComparisonOperator::Equals,
+                    rhs: Expr::Constant(Constant::Decimal(2), Span::default()),
+                    span: Span::default(),
+                }),
+            ],
+            group_by: vec!["pk".into()],
+            order_by: vec![OrderBy {
+                name: "order_column".into(),
+                ordering: Ordering::Desc,
+                span: Span::default(),
+            }],
+            per_partition_limit: Some(Limit::Literal(1)),
+            limit: Some(Limit::Literal(9999)),
             allow_filtering: true,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_strict_clause_out_of_order() {
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        parse_with(
+            "SELECT field FROM table ORDER BY c DESC WHERE foo = 1",
+            strict
+        ),
+        Err(ParseError::ClauseOutOfOrder {
+            clause: "WHERE",
+            after: "ORDER BY",
+            offset: 40,
+        })
+    );
+    assert_eq!(
+        parse_with("SELECT field FROM table LIMIT 1 LIMIT 2", strict),
+        Err(ParseError::DuplicateClause {
+            clause: "LIMIT",
+            offset: 32,
+        })
+    );
+    assert_eq!(
+        parse_with("SELECT DISTINCT JSON field FROM table", strict),
+        Err(ParseError::Syntax { offset: 21 })
+    );
+}
+
+#[test]
+fn test_select_duplicate_clause() {
+    // Rejected in either mode, rather than the last clause silently winning
+    for options in [
+        ParseOptions::default(),
+        ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        },
+    ] {
+        assert_eq!(
+            parse_with("SELECT * FROM t LIMIT 1 LIMIT 2", options),
+            Err(ParseError::DuplicateClause {
+                clause: "LIMIT",
+                offset: 24,
+            })
+        );
+    }
+    let error = parse_with(
+        "SELECT * FROM t WHERE a = 1 ALLOW FILTERING WHERE b = 2",
+        ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        ParseError::DuplicateClause {
+            clause: "WHERE",
+            offset: 44,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "WHERE clause at byte 44 appears more than once"
+    );
+}
+
+#[test]
+fn test_select_strict_requires_separators() {
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        parse_with("SELECT a b FROM t", strict),
+        Err(ParseError::Syntax { offset: 9 })
+    );
+    assert_eq!(
+        parse_with("SELECT a FROM t WHERE x = 1 y = 2", strict),
+        Err(ParseError::Syntax { offset: 28 })
+    );
+}
+
+#[test]
+fn test_select_lenient_clause_order() {
+    assert_parses(
+        &["SELECT field FROM table limit 1 WHERE foo = 1"],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![SelectElement {
+                expr: Expr::Name("field".into()),
+                as_alias: None,
+                span: Span::default(),
+            }],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("foo".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            })],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: Some(Limit::Literal(1)),
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_multiple_statements() {
+    let select = |name: &str| {
+        Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![SelectElement {
+                expr: Expr::Wildcard(Span::default()),
+                as_alias: None,
+                span: Span::default(),
+            }],
+            from: QualifiedName {
+                keyspace: None,
+                name: name.into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+            using: None,
+        })
+    };
+    assert_parses(
+        &[
+            "SELECT * FROM foo; SELECT * FROM bar",
+            "  SELECT * FROM foo;\nSELECT * FROM bar;\n",
+        ],
+        vec![select("foo"), select("bar")],
+    );
+    assert_eq!(
+        parse_with(
+            "SELECT * FROM foo SELECT * FROM bar",
+            ParseOptions::default()
+        ),
+        Err(ParseError::Syntax { offset: 18 })
+    );
+}
+
+#[test]
+fn test_integer_range() {
+    let rhs = |query: &str| match parse(query).remove(0) {
+        Statement::Select(mut select) => match select.where_.remove(0) {
+            RelationElement::Comparison(comparison) => comparison.rhs,
+            relation => panic!("expected a comparison, got {:?}", relation),
+        },
+        statement => panic!("expected a select, got {:?}", statement),
+    };
+    assert_eq_ignoring_spans(
+        &rhs("SELECT * FROM t WHERE k = -9223372036854775808"),
+        &Expr::Constant(Constant::Decimal(i64::MIN), Span::default()),
+    );
+    assert_eq_ignoring_spans(
+        &rhs("SELECT * FROM t WHERE k = 9223372036854775807"),
+        &Expr::Constant(Constant::Decimal(i64::MAX), Span::default()),
+    );
+
+    for query in [
+        "SELECT * FROM t WHERE k = 99999999999999999999",
+        "SELECT * FROM t WHERE k = -9223372036854775809",
+        "SELECT * FROM t LIMIT 99999999999999999999",
+        "INSERT INTO t (k) VALUES (0) USING TTL 99999999999999999999",
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 99999999999999999999}",
+    ] {
+        assert!(
+            matches!(
+                parse_with(query, ParseOptions::default()),
+                Err(ParseError::Syntax { .. })
+            ),
+            "{}",
+            query
+        );
+    }
+}
+
+#[test]
+fn test_insert_using() {
+    assert_parses(
+        &[
+            "INSERT INTO table (pk) VALUES (1) USING TTL 86400 AND TIMESTAMP 123456789",
+            "insert into table (pk) values (1)   using   ttl   86400   and   timestamp   123456789",
+        ],
+        vec![Statement::Insert(Insert {
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            columns: vec!["pk".into()],
+            values: vec![Expr::Constant(Constant::Decimal(1), Span::default())],
+            condition: None,
+            using: Some(UsingClause {
+                ttl: Some(UsingValue::Integer(86400)),
+                timestamp: Some(UsingValue::Integer(123456789)),
+                timeout: None,
+                span: Span::default(),
+            }),
         })],
     );
 }
+
+#[test]
+fn test_update_delete_using() {
+    assert_parses(
+        &["UPDATE table USING TTL ? AND TIMEOUT 500ms SET a = 1 WHERE pk = 1"],
+        vec![Statement::Update(Update {
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            using: Some(UsingClause {
+                ttl: Some(UsingValue::BindMarker(BindMarker::Anonymous)),
+                timestamp: None,
+                timeout: Some(UsingValue::Duration("500ms".to_string())),
+                span: Span::default(),
+            }),
+            set: vec![Assignment {
+                lhs: Expr::Name("a".into()),
+                operator: AssignmentOperator::Set,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            }],
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("pk".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            })],
+            condition: None,
+        })],
+    );
+    assert_parses(
+        &["DELETE FROM table USING TIMESTAMP :ts WHERE pk = 1"],
+        vec![Statement::Delete(Delete {
+            columns: vec![],
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            using: Some(UsingClause {
+                ttl: None,
+                timestamp: Some(UsingValue::BindMarker(BindMarker::Named("ts".to_string()))),
+                timeout: None,
+                span: Span::default(),
+            }),
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("pk".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            })],
+            condition: None,
+        })],
+    );
+}
+
+#[test]
+fn test_using_repeated_option() {
+    for query in [
+        "INSERT INTO t (k) VALUES (0) USING TTL 1 AND TTL 2",
+        "UPDATE t USING TIMESTAMP 1 AND TTL 2 AND TIMESTAMP 3 SET a = 1 WHERE k = 0",
+        "DELETE FROM t USING TIMEOUT 1s AND TIMEOUT ? WHERE k = 0",
+        "SELECT * FROM t USING TIMEOUT 1s AND TIMEOUT 2s",
+    ] {
+        assert!(
+            matches!(
+                parse_with(query, ParseOptions::default()),
+                Err(ParseError::Syntax { .. })
+            ),
+            "{}",
+            query
+        );
+    }
+}
+
+#[test]
+fn test_select_using_timeout() {
+    assert_parses(
+        &["SELECT * FROM table LIMIT 10 ALLOW FILTERING USING TIMEOUT 1h30m"],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![SelectElement {
+                expr: Expr::Wildcard(Span::default()),
+                as_alias: None,
+                span: Span::default(),
+            }],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: Some(Limit::Literal(10)),
+            allow_filtering: true,
+            using: Some(UsingClause {
+                ttl: None,
+                timestamp: None,
+                timeout: Some(UsingValue::Duration("1h30m".to_string())),
+                span: Span::default(),
+            }),
+        })],
+    );
+    assert_eq!(
+        parse_with(
+            "SELECT * FROM table USING TIMEOUT 1s LIMIT 10",
+            ParseOptions {
+                strict: true,
+                ..ParseOptions::default()
+            }
+        ),
+        Err(ParseError::ClauseOutOfOrder {
+            clause: "LIMIT",
+            after: "USING",
+            offset: 37,
+        })
+    );
+}
+
+#[test]
+fn test_batch() {
+    let insert = |pk| Insert {
+        table: QualifiedName {
+            keyspace: None,
+            name: "table".into(),
+            span: Span::default(),
+        },
+        columns: vec!["pk".into()],
+        values: vec![Expr::Constant(Constant::Decimal(pk), Span::default())],
+        condition: None,
+        using: None,
+    };
+    assert_parses(
+        &[
+            "BEGIN BATCH INSERT INTO table (pk) VALUES (1); INSERT INTO table (pk) VALUES (2); APPLY BATCH",
+            "begin batch\n  insert into table (pk) values (1)\n  insert into table (pk) values (2)\napply batch;",
+        ],
+        vec![Statement::Batch(Batch {
+            batch_type: BatchType::Logged,
+            using: None,
+            statements: vec![
+                BatchStatement::Insert(insert(1)),
+                BatchStatement::Insert(insert(2)),
+            ],
+        })],
+    );
+    assert_parses(
+        &["BEGIN UNLOGGED BATCH USING TIMESTAMP 1 DELETE FROM table WHERE pk = 1 IF EXISTS; APPLY BATCH"],
+        vec![Statement::Batch(Batch {
+            batch_type: BatchType::Unlogged,
+            using: Some(UsingClause {
+                ttl: None,
+                timestamp: Some(UsingValue::Integer(1)),
+                timeout: None,
+                span: Span::default(),
+            }),
+            statements: vec![BatchStatement::Delete(Delete {
+                columns: vec![],
+                table: QualifiedName {
+                    keyspace: None,
+                    name: "table".into(),
+                    span: Span::default(),
+                },
+                using: None,
+                where_: vec![RelationElement::Comparison(RelationComparison {
+                    lhs: Expr::Name("pk".into()),
+                    operator: ComparisonOperator::Equals,
+                    rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                    span: Span::default(),
+                })],
+                condition: Some(Condition::Exists),
+            })],
+        })],
+    );
+    assert!(parse("BEGIN UNLOGGED BATCH USING TIMESTAMP 1 DELETE FROM table WHERE pk = 1 IF EXISTS; APPLY BATCH")[0].is_lwt());
+    assert!(
+        !parse("BEGIN COUNTER BATCH UPDATE table SET c = c + 1 WHERE pk = 1 APPLY BATCH")[0]
+            .is_lwt()
+    );
+}
+
+#[test]
+fn test_select_quoted_identifiers() {
+    assert_parses(
+        &[
+            r#"SELECT "Mixed", Upper AS "Alias""" FROM "Ks".Table"#,
+            r#"select "Mixed" , upper as "Alias""" from "Ks".table"#,
+        ],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![
+                SelectElement {
+                    expr: Expr::Name("Mixed".into()),
+                    as_alias: None,
+                    span: Span::default(),
+                },
+                SelectElement {
+                    expr: Expr::Name("upper".into()),
+                    as_alias: Some("Alias\"".into()),
+                    span: Span::default(),
+                },
+            ],
+            from: QualifiedName {
+                keyspace: Some("Ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_function_call() {
+    assert_parses(
+        &["SELECT count(*), ks.myFunc(a, 'b') FROM table WHERE token(pk) > ?"],
+        vec![Statement::Select(Select {
+            distinct: false,
+            json: false,
+            select: vec![
+                SelectElement {
+                    expr: Expr::FunctionCall(FunctionCall {
+                        function: QualifiedName {
+                            keyspace: None,
+                            name: "count".into(),
+                            span: Span::default(),
+                        },
+                        args: vec![Expr::Wildcard(Span::default())],
+                        span: Span::default(),
+                    }),
+                    as_alias: None,
+                    span: Span::default(),
+                },
+                SelectElement {
+                    expr: Expr::FunctionCall(FunctionCall {
+                        function: QualifiedName {
+                            keyspace: Some("ks".into()),
+                            name: "myfunc".into(),
+                            span: Span::default(),
+                        },
+                        args: vec![
+                            Expr::Name("a".into()),
+                            Expr::Constant(Constant::String("b".to_string()), Span::default()),
+                        ],
+                        span: Span::default(),
+                    }),
+                    as_alias: None,
+                    span: Span::default(),
+                },
+            ],
+            from: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            where_: vec![RelationElement::Comparison(RelationComparison {
+                lhs: Expr::FunctionCall(FunctionCall {
+                    function: QualifiedName {
+                        keyspace: None,
+                        name: "token".into(),
+                        span: Span::default(),
+                    },
+                    args: vec![Expr::Name("pk".into())],
+                    span: Span::default(),
+                }),
+                operator: ComparisonOperator::GreaterThan,
+                rhs: Expr::BindMarker(BindMarker::Anonymous, Span::default()),
+                span: Span::default(),
+            })],
+            group_by: vec![],
+            order_by: vec![],
+            per_partition_limit: None,
+            limit: None,
+            allow_filtering: false,
+            using: None,
+        })],
+    );
+}
+
+#[test]
+fn test_select_json_keys() {
+    let json_keys = |query: &str| match &parse(query)[0] {
+        Statement::Select(select) => select.json_keys(),
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        json_keys(
+            r#"SELECT JSON a, "Mixed", b AS "Alias", c AS alias, count(*), toJson(m), writetime(a), max("Col"), ks.f(a, 'x'), udf(a) FROM ks2.table"#
+        ),
+        Some(vec![
+            "a".to_string(),
+            "\"Mixed\"".to_string(),
+            "\"Alias\"".to_string(),
+            "alias".to_string(),
+            "system.count(*)".to_string(),
+            "system.tojson(m)".to_string(),
+            "writetime(a)".to_string(),
+            "\"system.max(Col)\"".to_string(),
+            "ks.f(a, 'x')".to_string(),
+            "ks2.udf(a)".to_string(),
+        ])
+    );
+    assert_eq!(
+        json_keys("SELECT JSON abs(a), collection_count(l), map_keys(m), mask_inner(s, 1, 2), to_json(m), blob_as_int(b), int_as_blob(a) FROM ks.t"),
+        Some(vec![
+            "system.abs(a)".to_string(),
+            "system.collection_count(l)".to_string(),
+            "system.map_keys(m)".to_string(),
+            "system.mask_inner(s, 1, 2)".to_string(),
+            "system.to_json(m)".to_string(),
+            "system.blob_as_int(b)".to_string(),
+            "system.int_as_blob(a)".to_string(),
+        ])
+    );
+    assert_eq!(json_keys("SELECT JSON * FROM table"), None);
+    assert_eq!(json_keys("SELECT a FROM table"), None);
+}
+
+#[test]
+fn test_create_keyspace() {
+    assert_parses(
+        &[
+            "CREATE KEYSPACE IF NOT EXISTS ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 3} AND durable_writes = false",
+            "create keyspace if not exists ks with replication={'class':'org.apache.cassandra.locator.SimpleStrategy','replication_factor':'3'} and durable_writes=false",
+        ],
+        vec![Statement::CreateKeyspace(CreateKeyspace {
+            if_not_exists: true,
+            name: "ks".into(),
+            replication: ReplicationStrategy::Simple {
+                replication_factor: 3,
+            },
+            durable_writes: Some(false),
+        })],
+    );
+    assert_parses(
+        &["CREATE KEYSPACE \"Ks\" WITH replication = {'class': 'NetworkTopologyStrategy', 'dc1': 3, 'dc2': '2'}"],
+        vec![Statement::CreateKeyspace(CreateKeyspace {
+            if_not_exists: false,
+            name: "Ks".into(),
+            replication: ReplicationStrategy::NetworkTopology {
+                replication_factor: None,
+                datacenters: vec![("dc1".to_string(), 3), ("dc2".to_string(), 2)],
+            },
+            durable_writes: None,
+        })],
+    );
+    assert_parses(
+        &[
+            "CREATE KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'replication_factor': 3, 'dc2': 1}",
+            "CREATE KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'dc2': 1, 'replication_factor': '3'}",
+        ],
+        vec![Statement::CreateKeyspace(CreateKeyspace {
+            if_not_exists: false,
+            name: "ks".into(),
+            replication: ReplicationStrategy::NetworkTopology {
+                replication_factor: Some(3),
+                datacenters: vec![("dc2".to_string(), 1)],
+            },
+            durable_writes: None,
+        })],
+    );
+    assert_parses(
+        &["CREATE KEYSPACE ks WITH replication = {'class': 'com.example.CustomStrategy', 'foo': 'bar'}"],
+        vec![Statement::CreateKeyspace(CreateKeyspace {
+            if_not_exists: false,
+            name: "ks".into(),
+            replication: ReplicationStrategy::Other {
+                class: "com.example.CustomStrategy".to_string(),
+                options: vec![("foo".to_string(), "bar".to_string())],
+            },
+            durable_writes: None,
+        })],
+    );
+    assert_eq!(
+        parse_with(
+            "CREATE KEYSPACE ks WITH durable_writes = true",
+            ParseOptions::default()
+        ),
+        Err(ParseError::Syntax { offset: 15 })
+    );
+}
+
+#[test]
+fn test_alter_drop_keyspace() {
+    assert_parses(
+        &["ALTER KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'dc1': '3/1'}"],
+        vec![Statement::AlterKeyspace(AlterKeyspace {
+            if_exists: false,
+            name: "ks".into(),
+            replication: Some(ReplicationStrategy::Other {
+                class: "NetworkTopologyStrategy".to_string(),
+                options: vec![("dc1".to_string(), "3/1".to_string())],
+            }),
+            durable_writes: None,
+        })],
+    );
+    assert_parses(
+        &["ALTER KEYSPACE IF EXISTS ks WITH durable_writes = true"],
+        vec![Statement::AlterKeyspace(AlterKeyspace {
+            if_exists: true,
+            name: "ks".into(),
+            replication: None,
+            durable_writes: Some(true),
+        })],
+    );
+    assert_parses(
+        &[
+            "DROP KEYSPACE IF EXISTS ks",
+            "drop   keyspace   if   exists   ks",
+        ],
+        vec![Statement::DropKeyspace(DropKeyspace {
+            if_exists: true,
+            name: "ks".into(),
+        })],
+    );
+}
+
+#[test]
+fn test_create_table() {
+    assert_parses(
+        &[
+            "CREATE TABLE IF NOT EXISTS ks.events (
+                tenant text,
+                bucket int,
+                time timeuuid,
+                seq bigint,
+                owner text STATIC,
+                tags frozen<set<text>>,
+                attrs map<text, frozen<list<int>>>,
+                point tuple<double, double>,
+                addr ks.address,
+                legacy 'org.apache.cassandra.db.marshal.DateType',
+                PRIMARY KEY ((tenant, bucket), time, seq)
+            ) WITH CLUSTERING ORDER BY (time DESC, seq ASC)
+                AND compaction = {'class': 'TimeWindowCompactionStrategy', 'compaction_window_size': 1}
+                AND bloom_filter_fp_chance = 0.01
+                AND default_time_to_live = 0
+                AND comment = 'events'",
+            "create table if not exists ks.events(tenant text,bucket int,time timeuuid,seq bigint,owner text static,tags frozen < set < text > >,attrs map<text,frozen<list<int>>>,point tuple<double,double>,addr ks.address,legacy 'org.apache.cassandra.db.marshal.DateType',primary key((tenant,bucket),time,seq)) with clustering order by(time desc,seq asc) and compaction={'class':'TimeWindowCompactionStrategy','compaction_window_size':1} and bloom_filter_fp_chance=0.01 and default_time_to_live=0 and comment='events'",
+        ],
+        vec![Statement::CreateTable(CreateTable {
+            if_not_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "events".into(),
+                span: Span::default(),
+            },
+            columns: vec![
+                ColumnDefinition {
+                    name: "tenant".into(),
+                    data_type: CqlType::Native(NativeType::Text),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "bucket".into(),
+                    data_type: CqlType::Native(NativeType::Int),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "time".into(),
+                    data_type: CqlType::Native(NativeType::Timeuuid),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "seq".into(),
+                    data_type: CqlType::Native(NativeType::Bigint),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "owner".into(),
+                    data_type: CqlType::Native(NativeType::Text),
+                    static_: true,
+                },
+                ColumnDefinition {
+                    name: "tags".into(),
+                    data_type: CqlType::Frozen(Box::new(CqlType::Set(Box::new(
+                        CqlType::Native(NativeType::Text),
+                    )))),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "attrs".into(),
+                    data_type: CqlType::Map(
+                        Box::new(CqlType::Native(NativeType::Text)),
+                        Box::new(CqlType::Frozen(Box::new(CqlType::List(Box::new(
+                            CqlType::Native(NativeType::Int),
+                        ))))),
+                    ),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "point".into(),
+                    data_type: CqlType::Tuple(vec![
+                        CqlType::Native(NativeType::Double),
+                        CqlType::Native(NativeType::Double),
+                    ]),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "addr".into(),
+                    data_type: CqlType::UserDefined(QualifiedName {
+                        keyspace: Some("ks".into()),
+                        name: "address".into(),
+                        span: Span::default(),
+                    }),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "legacy".into(),
+                    data_type: CqlType::Custom(
+                        "org.apache.cassandra.db.marshal.DateType".to_string(),
+                    ),
+                    static_: false,
+                },
+            ],
+            partition_key: vec!["tenant".into(), "bucket".into()],
+            clustering_key: vec!["time".into(), "seq".into()],
+            clustering_order: vec![
+                OrderBy {
+                    name: "time".into(),
+                    ordering: Ordering::Desc,
+                    span: Span::default(),
+                },
+                OrderBy {
+                    name: "seq".into(),
+                    ordering: Ordering::Asc,
+                    span: Span::default(),
+                },
+            ],
+            compact_storage: false,
+            options: vec![
+                Property {
+                    name: "compaction".into(),
+                    value: PropertyValue::Map(vec![
+                        (
+                            Constant::String("class".to_string()),
+                            Constant::String("TimeWindowCompactionStrategy".to_string()),
+                        ),
+                        (
+                            Constant::String("compaction_window_size".to_string()),
+                            Constant::Decimal(1),
+                        ),
+                    ]),
+                },
+                Property {
+                    name: "bloom_filter_fp_chance".into(),
+                    value: PropertyValue::Constant(Constant::Float(0.01)),
+                },
+                Property {
+                    name: "default_time_to_live".into(),
+                    value: PropertyValue::Constant(Constant::Decimal(0)),
+                },
+                Property {
+                    name: "comment".into(),
+                    value: PropertyValue::Constant(Constant::String("events".to_string())),
+                },
+            ],
+        })],
+    );
+}
+
+#[test]
+fn test_create_table_inline_primary_key() {
+    assert_parses(
+        &["CREATE TABLE users (id uuid PRIMARY KEY, name text) WITH COMPACT STORAGE"],
+        vec![Statement::CreateTable(CreateTable {
+            if_not_exists: false,
+            name: QualifiedName {
+                keyspace: None,
+                name: "users".into(),
+                span: Span::default(),
+            },
+            columns: vec![
+                ColumnDefinition {
+                    name: "id".into(),
+                    data_type: CqlType::Native(NativeType::Uuid),
+                    static_: false,
+                },
+                ColumnDefinition {
+                    name: "name".into(),
+                    data_type: CqlType::Native(NativeType::Text),
+                    static_: false,
+                },
+            ],
+            partition_key: vec!["id".into()],
+            clustering_key: vec![],
+            clustering_order: vec![],
+            compact_storage: true,
+            options: vec![],
+        })],
+    );
+    assert_eq!(
+        parse_with(
+            "CREATE TABLE users (id uuid, name text)",
+            ParseOptions::default()
+        ),
+        Err(ParseError::Syntax { offset: 12 })
+    );
+    for query in [
+        "CREATE TABLE t (a int, b int, PRIMARY KEY ((), b))",
+        "CREATE TABLE t (a int, b int, PRIMARY KEY ())",
+        "CREATE TABLE t (a int, b int, PRIMARY KEY (a, ()))",
+    ] {
+        assert!(
+            matches!(
+                parse_with(query, ParseOptions::default()),
+                Err(ParseError::Syntax { .. })
+            ),
+            "{}",
+            query
+        );
+    }
+}
+
+#[test]
+fn test_alter_table() {
+    let alter = |operation| {
+        vec![Statement::AlterTable(AlterTable {
+            if_exists: false,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+            operation,
+        })]
+    };
+    assert_parses(
+        &["ALTER TABLE ks.table ADD owner text static"],
+        alter(AlterTableOperation::Add(vec![ColumnDefinition {
+            name: "owner".into(),
+            data_type: CqlType::Native(NativeType::Text),
+            static_: true,
+        }])),
+    );
+    assert_parses(
+        &[
+            "ALTER TABLE ks.table ADD (a int, b list<text>)",
+            "alter table ks.table add(a int,b list<text>)",
+        ],
+        alter(AlterTableOperation::Add(vec![
+            ColumnDefinition {
+                name: "a".into(),
+                data_type: CqlType::Native(NativeType::Int),
+                static_: false,
+            },
+            ColumnDefinition {
+                name: "b".into(),
+                data_type: CqlType::List(Box::new(CqlType::Native(NativeType::Text))),
+                static_: false,
+            },
+        ])),
+    );
+    assert_parses(
+        &["ALTER TABLE ks.table DROP a"],
+        alter(AlterTableOperation::Drop(vec!["a".into()])),
+    );
+    assert_parses(
+        &["ALTER TABLE ks.table DROP (a, b)"],
+        alter(AlterTableOperation::Drop(vec!["a".into(), "b".into()])),
+    );
+    assert_parses(
+        &["ALTER TABLE ks.table RENAME a TO b AND c TO d"],
+        alter(AlterTableOperation::Rename(vec![
+            ("a".into(), "b".into()),
+            ("c".into(), "d".into()),
+        ])),
+    );
+    assert_parses(
+        &["ALTER TABLE ks.table WITH gc_grace_seconds = 3600 AND caching = {'keys': 'ALL'}"],
+        alter(AlterTableOperation::With(vec![
+            Property {
+                name: "gc_grace_seconds".into(),
+                value: PropertyValue::Constant(Constant::Decimal(3600)),
+            },
+            Property {
+                name: "caching".into(),
+                value: PropertyValue::Map(vec![(
+                    Constant::String("keys".to_string()),
+                    Constant::String("ALL".to_string()),
+                )]),
+            },
+        ])),
+    );
+}
+
+#[test]
+fn test_drop_table_truncate() {
+    assert_parses(
+        &[
+            "DROP TABLE IF EXISTS ks.table",
+            "drop columnfamily if exists ks.table",
+        ],
+        vec![Statement::DropTable(DropTable {
+            if_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+        })],
+    );
+    assert_parses(
+        &["TRUNCATE ks.table", "TRUNCATE TABLE ks.table"],
+        vec![Statement::Truncate(Truncate {
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+        })],
+    );
+}
+
+#[test]
+fn test_create_index() {
+    assert_parses(
+        &[
+            "CREATE INDEX IF NOT EXISTS tags_idx ON ks.table (keys(tags))",
+            "create index if not exists tags_idx on ks.table(KEYS ( tags ))",
+        ],
+        vec![Statement::CreateIndex(CreateIndex {
+            custom: false,
+            if_not_exists: true,
+            name: Some("tags_idx".into()),
+            table: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "table".into(),
+                span: Span::default(),
+            },
+            targets: vec![IndexTarget::Keys("tags".into())],
+            using: None,
+            options: vec![],
+        })],
+    );
+    assert_parses(
+        &["CREATE INDEX ON table (value)"],
+        vec![Statement::CreateIndex(CreateIndex {
+            custom: false,
+            if_not_exists: false,
+            name: None,
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            targets: vec![IndexTarget::Column("value".into())],
+            using: None,
+            options: vec![],
+        })],
+    );
+    assert_parses(
+        &["CREATE CUSTOM INDEX name_idx ON table (full(name)) USING 'org.apache.cassandra.index.sasi.SASIIndex' WITH OPTIONS = {'mode': 'CONTAINS'}"],
+        vec![Statement::CreateIndex(CreateIndex {
+            custom: true,
+            if_not_exists: false,
+            name: Some("name_idx".into()),
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            targets: vec![IndexTarget::Full("name".into())],
+            using: Some("org.apache.cassandra.index.sasi.SASIIndex".to_string()),
+            options: vec![(
+                Constant::String("mode".to_string()),
+                Constant::String("CONTAINS".to_string()),
+            )],
+        })],
+    );
+    assert_parses(
+        &["CREATE INDEX ON table (values(l), entries(m)) USING 'sai'"],
+        vec![Statement::CreateIndex(CreateIndex {
+            custom: false,
+            if_not_exists: false,
+            name: None,
+            table: QualifiedName {
+                keyspace: None,
+                name: "table".into(),
+                span: Span::default(),
+            },
+            targets: vec![
+                IndexTarget::Values("l".into()),
+                IndexTarget::Entries("m".into()),
+            ],
+            using: Some("sai".to_string()),
+            options: vec![],
+        })],
+    );
+}
+
+#[test]
+fn test_drop_index() {
+    assert_parses(
+        &["DROP INDEX IF EXISTS ks.tags_idx"],
+        vec![Statement::DropIndex(DropIndex {
+            if_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "tags_idx".into(),
+                span: Span::default(),
+            },
+        })],
+    );
+}
+
+#[test]
+fn test_create_materialized_view() {
+    let statements = parse(
+        "CREATE MATERIALIZED VIEW IF NOT EXISTS ks.users_by_email AS
+            SELECT email, id, name FROM ks.users
+            WHERE email IS NOT NULL AND id IS NOT NULL
+            PRIMARY KEY (email, id)
+            WITH CLUSTERING ORDER BY (id DESC) AND comment = 'lookup'",
+    );
+    assert_eq_ignoring_spans(
+        &statements,
+        &vec![Statement::CreateMaterializedView(CreateMaterializedView {
+            if_not_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "users_by_email".into(),
+                span: Span::default(),
+            },
+            select: Select {
+                distinct: false,
+                json: false,
+                select: vec![
+                    SelectElement {
+                        expr: Expr::Name("email".into()),
+                        as_alias: None,
+                        span: Span::default(),
+                    },
+                    SelectElement {
+                        expr: Expr::Name("id".into()),
+                        as_alias: None,
+                        span: Span::default(),
+                    },
+                    SelectElement {
+                        expr: Expr::Name("name".into()),
+                        as_alias: None,
+                        span: Span::default(),
+                    },
+                ],
+                from: QualifiedName {
+                    keyspace: Some("ks".into()),
+                    name: "users".into(),
+                    span: Span::default(),
+                },
+                where_: vec![
+                    RelationElement::IsNotNull(RelationIsNotNull {
+                        column: "email".into(),
+                        span: Span::default(),
+                    }),
+                    RelationElement::IsNotNull(RelationIsNotNull {
+                        column: "id".into(),
+                        span: Span::default(),
+                    }),
+                ],
+                group_by: vec![],
+                order_by: vec![],
+                per_partition_limit: None,
+                limit: None,
+                allow_filtering: false,
+                using: None,
+            },
+            partition_key: vec!["email".into()],
+            clustering_key: vec!["id".into()],
+            clustering_order: vec![OrderBy {
+                name: "id".into(),
+                ordering: Ordering::Desc,
+                span: Span::default(),
+            }],
+            options: vec![Property {
+                name: "comment".into(),
+                value: PropertyValue::Constant(Constant::String("lookup".to_string())),
+            }],
+        })],
+    );
+    match &statements[0] {
+        Statement::CreateMaterializedView(view) => assert_eq_ignoring_spans(
+            view.base_table(),
+            &QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "users".into(),
+                span: Span::default(),
+            },
+        ),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_alter_drop_materialized_view() {
+    assert_parses(
+        &["ALTER MATERIALIZED VIEW ks.view WITH gc_grace_seconds = 0"],
+        vec![Statement::AlterMaterializedView(AlterMaterializedView {
+            if_exists: false,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "view".into(),
+                span: Span::default(),
+            },
+            options: vec![Property {
+                name: "gc_grace_seconds".into(),
+                value: PropertyValue::Constant(Constant::Decimal(0)),
+            }],
+        })],
+    );
+    assert_parses(
+        &["DROP MATERIALIZED VIEW IF EXISTS ks.view"],
+        vec![Statement::DropMaterializedView(DropMaterializedView {
+            if_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "view".into(),
+                span: Span::default(),
+            },
+        })],
+    );
+}
+
+#[test]
+fn test_create_type() {
+    assert_parses(
+        &["CREATE TYPE IF NOT EXISTS ks.address (street text, zip int, phones frozen<list<ks.phone>>)"],
+        vec![Statement::CreateType(CreateType {
+            if_not_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "address".into(),
+                span: Span::default(),
+            },
+            fields: vec![
+                FieldDefinition {
+                    name: "street".into(),
+                    data_type: CqlType::Native(NativeType::Text),
+                },
+                FieldDefinition {
+                    name: "zip".into(),
+                    data_type: CqlType::Native(NativeType::Int),
+                },
+                FieldDefinition {
+                    name: "phones".into(),
+                    data_type: CqlType::Frozen(Box::new(CqlType::List(Box::new(
+                        CqlType::UserDefined(QualifiedName {
+                            keyspace: Some("ks".into()),
+                            name: "phone".into(),
+                            span: Span::default(),
+                        }),
+                    )))),
+                },
+            ],
+        })],
+    );
+}
+
+#[test]
+fn test_alter_drop_type() {
+    let address = QualifiedName {
+        keyspace: None,
+        name: "address".into(),
+        span: Span::default(),
+    };
+    assert_parses(
+        &["ALTER TYPE address ADD country text"],
+        vec![Statement::AlterType(AlterType {
+            if_exists: false,
+            name: address.clone(),
+            operation: AlterTypeOperation::Add(FieldDefinition {
+                name: "country".into(),
+                data_type: CqlType::Native(NativeType::Text),
+            }),
+        })],
+    );
+    assert_parses(
+        &["ALTER TYPE address RENAME zip TO postcode AND street TO line1"],
+        vec![Statement::AlterType(AlterType {
+            if_exists: false,
+            name: address.clone(),
+            operation: AlterTypeOperation::Rename(vec![
+                ("zip".into(), "postcode".into()),
+                ("street".into(), "line1".into()),
+            ]),
+        })],
+    );
+    assert_parses(
+        &["DROP TYPE IF EXISTS address"],
+        vec![Statement::DropType(DropType {
+            if_exists: true,
+            name: address,
+        })],
+    );
+}
+
+#[test]
+fn test_create_function() {
+    let body = "\n    if (a == null) { return 'none'; }\n    return a.toString();\n";
+    assert_parses(
+        &[&format!(
+            "CREATE OR REPLACE FUNCTION IF NOT EXISTS ks.describe (a int, b list<text>)
+                RETURNS NULL ON NULL INPUT
+                RETURNS text
+                LANGUAGE java
+                AS $${}$$",
+            body
+        )],
+        vec![Statement::CreateFunction(CreateFunction {
+            or_replace: true,
+            if_not_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "describe".into(),
+                span: Span::default(),
+            },
+            arguments: vec![
+                FunctionArgument {
+                    name: "a".into(),
+                    data_type: CqlType::Native(NativeType::Int),
+                },
+                FunctionArgument {
+                    name: "b".into(),
+                    data_type: CqlType::List(Box::new(CqlType::Native(NativeType::Text))),
+                },
+            ],
+            called_on_null_input: false,
+            return_type: CqlType::Native(NativeType::Text),
+            language: "java".to_string(),
+            body: Constant::CodeBlock(body.to_string()),
+        })],
+    );
+    assert_parses(
+        &["CREATE FUNCTION f () CALLED ON NULL INPUT RETURNS int LANGUAGE javascript AS '1'"],
+        vec![Statement::CreateFunction(CreateFunction {
+            or_replace: false,
+            if_not_exists: false,
+            name: QualifiedName {
+                keyspace: None,
+                name: "f".into(),
+                span: Span::default(),
+            },
+            arguments: vec![],
+            called_on_null_input: true,
+            return_type: CqlType::Native(NativeType::Int),
+            language: "javascript".to_string(),
+            body: Constant::String("1".to_string()),
+        })],
+    );
+}
+
+#[test]
+fn test_create_aggregate() {
+    assert_parses(
+        &["CREATE AGGREGATE IF NOT EXISTS ks.average (int)
+            SFUNC avg_state STYPE tuple<int, bigint>
+            FINALFUNC avg_final INITCOND (0, 0)"],
+        vec![Statement::CreateAggregate(CreateAggregate {
+            or_replace: false,
+            if_not_exists: true,
+            name: QualifiedName {
+                keyspace: Some("ks".into()),
+                name: "average".into(),
+                span: Span::default(),
+            },
+            argument_types: vec![CqlType::Native(NativeType::Int)],
+            state_function: "avg_state".into(),
+            state_type: CqlType::Tuple(vec![
+                CqlType::Native(NativeType::Int),
+                CqlType::Native(NativeType::Bigint),
+            ]),
+            final_function: Some("avg_final".into()),
+            initial_condition: Some(Expr::Tuple(
+                vec![
+                    Expr::Constant(Constant::Decimal(0), Span::default()),
+                    Expr::Constant(Constant::Decimal(0), Span::default()),
+                ],
+                Span::default(),
+            )),
+        })],
+    );
+}
+
+#[test]
+fn test_drop_function_aggregate() {
+    let name = QualifiedName {
+        keyspace: Some("ks".into()),
+        name: "f".into(),
+        span: Span::default(),
+    };
+    assert_parses(
+        &["DROP FUNCTION IF EXISTS ks.f (int, text)"],
+        vec![Statement::DropFunction(DropFunction {
+            if_exists: true,
+            name: name.clone(),
+            argument_types: Some(vec![
+                CqlType::Native(NativeType::Int),
+                CqlType::Native(NativeType::Text),
+            ]),
+        })],
+    );
+    assert_parses(
+        &["DROP AGGREGATE ks.f"],
+        vec![Statement::DropAggregate(DropAggregate {
+            if_exists: false,
+            name,
+            argument_types: None,
+        })],
+    );
+}
+
+#[test]
+fn test_trigger() {
+    let table = QualifiedName {
+        keyspace: Some("ks".into()),
+        name: "t".into(),
+        span: Span::default(),
+    };
+    assert_parses(
+        &["CREATE TRIGGER IF NOT EXISTS audit ON ks.t USING 'org.example.AuditTrigger'"],
+        vec![Statement::CreateTrigger(CreateTrigger {
+            if_not_exists: true,
+            name: "audit".into(),
+            table: table.clone(),
+            using: "org.example.AuditTrigger".to_string(),
+        })],
+    );
+    assert_parses(
+        &["DROP TRIGGER audit ON ks.t"],
+        vec![Statement::DropTrigger(DropTrigger {
+            if_exists: false,
+            name: "audit".into(),
+            table,
+        })],
+    );
+}
+
+#[test]
+fn test_roles() {
+    assert_parses(
+        &["CREATE ROLE IF NOT EXISTS alice WITH PASSWORD = 'p@ss' AND LOGIN = true AND SUPERUSER = false
+            AND OPTIONS = {'custom': 1} AND ACCESS TO DATACENTERS {'dc1', 'dc2'}"],
+        vec![Statement::CreateRole(CreateRole {
+            if_not_exists: true,
+            name: "alice".into(),
+            options: vec![
+                RoleOption::Password("p@ss".to_string()),
+                RoleOption::Login(true),
+                RoleOption::Superuser(false),
+                RoleOption::Options(vec![(
+                    Constant::String("custom".to_string()),
+                    Constant::Decimal(1),
+                )]),
+                RoleOption::AccessToDatacenters(vec!["dc1".to_string(), "dc2".to_string()]),
+            ],
+        })],
+    );
+    assert_parses(
+        &["ALTER ROLE 'Bob' WITH HASHED PASSWORD = '$2a$10$x' AND ACCESS TO ALL DATACENTERS"],
+        vec![Statement::AlterRole(AlterRole {
+            name: "Bob".into(),
+            options: vec![
+                RoleOption::HashedPassword("$2a$10$x".to_string()),
+                RoleOption::AccessToAllDatacenters,
+            ],
+        })],
+    );
+    assert_parses(
+        &["DROP ROLE IF EXISTS alice"],
+        vec![Statement::DropRole(DropRole {
+            if_exists: true,
+            name: "alice".into(),
+        })],
+    );
+    assert_parses(
+        &["GRANT admin TO alice"],
+        vec![Statement::GrantRole(GrantRole {
+            role: "admin".into(),
+            grantee: "alice".into(),
+        })],
+    );
+    assert_parses(
+        &["REVOKE admin FROM alice"],
+        vec![Statement::RevokeRole(RevokeRole {
+            role: "admin".into(),
+            revokee: "alice".into(),
+        })],
+    );
+    assert_parses(
+        &["LIST ROLES OF alice NORECURSIVE"],
+        vec![Statement::ListRoles(ListRoles {
+            of: Some("alice".into()),
+            recursive: false,
+        })],
+    );
+    assert_parses(&["LIST USERS"], vec![Statement::ListUsers]);
+}
+
+#[test]
+fn test_users() {
+    assert_parses(
+        &["CREATE USER IF NOT EXISTS alice WITH PASSWORD 'secret' NOSUPERUSER"],
+        vec![Statement::CreateUser(CreateUser {
+            if_not_exists: true,
+            name: "alice".into(),
+            password: Some("secret".to_string()),
+            superuser: Some(false),
+        })],
+    );
+    assert_parses(
+        &["ALTER USER alice SUPERUSER"],
+        vec![Statement::AlterUser(AlterUser {
+            name: "alice".into(),
+            password: None,
+            superuser: Some(true),
+        })],
+    );
+    assert_parses(
+        &["DROP USER alice"],
+        vec![Statement::DropUser(DropUser {
+            if_exists: false,
+            name: "alice".into(),
+        })],
+    );
+}
+
+#[test]
+fn test_permissions() {
+    let grant = |input: &str| match parse(input).remove(0) {
+        Statement::GrantPermission(grant) => (grant.permission, grant.resource),
+        statement => panic!("unexpected statement {:?}", statement),
+    };
+    assert_eq!(
+        grant("GRANT ALL PERMISSIONS ON ALL KEYSPACES TO admin"),
+        (Permission::All, Resource::AllKeyspaces)
+    );
+    assert_eq_ignoring_spans(
+        &grant("GRANT SELECT ON KEYSPACE ks TO alice"),
+        &(Permission::Select, Resource::Keyspace("ks".into())),
+    );
+    let table = Resource::Table(QualifiedName {
+        keyspace: Some("ks".into()),
+        name: "t".into(),
+        span: Span::default(),
+    });
+    assert_eq_ignoring_spans(
+        &grant("GRANT MODIFY PERMISSION ON TABLE ks.t TO alice"),
+        &(Permission::Modify, table.clone()),
+    );
+    assert_eq_ignoring_spans(
+        &grant("GRANT MODIFY ON ks.t TO alice"),
+        &(Permission::Modify, table.clone()),
+    );
+    assert_eq_ignoring_spans(
+        &grant("GRANT AUTHORIZE ON ROLE bob TO alice"),
+        &(Permission::Authorize, Resource::Role("bob".into())),
+    );
+    assert_eq_ignoring_spans(
+        &grant("GRANT EXECUTE ON ALL FUNCTIONS IN KEYSPACE ks TO alice"),
+        &(
+            Permission::Execute,
+            Resource::AllFunctionsInKeyspace("ks".into()),
+        ),
+    );
+    assert_eq_ignoring_spans(
+        &grant("GRANT EXECUTE ON FUNCTION ks.f(int, text) TO alice"),
+        &(
+            Permission::Execute,
+            Resource::Function {
+                name: QualifiedName {
+                    keyspace: Some("ks".into()),
+                    name: "f".into(),
+                    span: Span::default(),
+                },
+                argument_types: vec![
+                    CqlType::Native(NativeType::Int),
+                    CqlType::Native(NativeType::Text),
+                ],
+            },
+        ),
+    );
+    assert_eq!(
+        grant("GRANT SELECT ON MBEANS 'org.apache.cassandra.db:*' TO alice"),
+        (
+            Permission::Select,
+            Resource::Mbeans("org.apache.cassandra.db:*".to_string())
+        )
+    );
+
+    assert_parses(
+        &["REVOKE DROP ON ks.t FROM alice"],
+        vec![Statement::RevokePermission(RevokePermission {
+            permission: Permission::Drop,
+            resource: table.clone(),
+            revokee: "alice".into(),
+        })],
+    );
+    assert_parses(
+        &["LIST ALL PERMISSIONS ON ks.t OF alice"],
+        vec![Statement::ListPermissions(ListPermissions {
+            permission: Permission::All,
+            resource: Some(table),
+            of: Some("alice".into()),
+            recursive: true,
+        })],
+    );
+    assert_parses(
+        &["LIST SELECT"],
+        vec![Statement::ListPermissions(ListPermissions {
+            permission: Permission::Select,
+            resource: None,
+            of: None,
+            recursive: true,
+        })],
+    );
+}
+
+#[test]
+fn test_redact() {
+    assert_eq!(
+        redact("CREATE ROLE alice WITH PASSWORD = 'it''s secret' AND LOGIN = true"),
+        "CREATE ROLE alice WITH PASSWORD = '*****' AND LOGIN = true"
+    );
+    assert_eq!(
+        redact(
+            "alter role alice with hashed password='$2a$10$x'; CREATE USER bob WITH PASSWORD 'pw'"
+        ),
+        "alter role alice with hashed password='*****'; CREATE USER bob WITH PASSWORD '*****'"
+    );
+    // Only the literal directly following the keyword is a password
+    assert_eq!(
+        redact("INSERT INTO t (\"password\", x) VALUES ('password', 'visible')"),
+        "INSERT INTO t (\"password\", x) VALUES ('password', 'visible')"
+    );
+    // Comments are skipped, whether they come before the statement or between the keyword and the literal
+    assert_eq!(
+        redact("-- don't log this\nCREATE ROLE r WITH PASSWORD = 'secret';"),
+        "-- don't log this\nCREATE ROLE r WITH PASSWORD = '*****';"
+    );
+    assert_eq!(
+        redact("/* it's */ CREATE ROLE r WITH PASSWORD /* x */ = 'secret';"),
+        "/* it's */ CREATE ROLE r WITH PASSWORD /* x */ = '*****';"
+    );
+    assert_eq!(
+        redact("ALTER ROLE r WITH HASHED PASSWORD = // the hash\n'$2a$10$x'"),
+        "ALTER ROLE r WITH HASHED PASSWORD = // the hash\n'*****'"
+    );
+    assert_eq!(
+        redact("CREATE USER u WITH PASSWORD -- 'fake'\n'secret'"),
+        "CREATE USER u WITH PASSWORD -- 'fake'\n'*****'"
+    );
+    assert!(parse_with(
+        "CREATE ROLE r WITH PASSWORD /* x */ = 'secret'",
+        ParseOptions::default()
+    )
+    .is_ok());
+    // Unparseable input is still redacted
+    assert_eq!(
+        redact("CREATE ROLE WITH PASSWORD 'pw"),
+        "CREATE ROLE WITH PASSWORD '*****'"
+    );
+
+    let statements =
+        parse("CREATE ROLE alice WITH PASSWORD = 'pw' AND LOGIN = true; ALTER USER bob WITH PASSWORD 'pw'");
+    assert_eq_ignoring_spans(
+        &statements
+            .iter()
+            .map(Statement::redacted)
+            .collect::<Vec<_>>(),
+        &vec![
+            Statement::CreateRole(CreateRole {
+                if_not_exists: false,
+                name: "alice".into(),
+                options: vec![
+                    RoleOption::Password("*****".to_string()),
+                    RoleOption::Login(true),
+                ],
+            }),
+            Statement::AlterUser(AlterUser {
+                name: "bob".into(),
+                password: Some("*****".to_string()),
+                superuser: None,
+            }),
+        ],
+    );
+    assert!(!format!("{:?}", statements[0].redacted()).contains("pw"));
+}
+
+#[test]
+fn test_use() {
+    assert_parses(&["USE ks", "use   KS"], vec![Statement::Use("ks".into())]);
+    assert_parses(&["USE \"Ks\""], vec![Statement::Use("Ks".into())]);
+}
+
+#[test]
+fn test_resolve_keyspaces() {
+    let mut statements = parse(
+        "TRUNCATE t1;
+        USE \"Other\";
+        SELECT * FROM t2;
+        BEGIN BATCH INSERT INTO t3 (a) VALUES (1); DELETE FROM ks.t4 WHERE a = 1; APPLY BATCH;
+        GRANT SELECT ON t5 TO alice;
+        DESCRIBE TABLE t6;
+        DESCRIBE MATERIALIZED VIEW v7;
+        DESCRIBE INDEX i8;
+        DROP INDEX i9;
+        DROP INDEX ks.i10",
+    );
+    resolve_keyspaces(&mut statements, Some("ks"));
+
+    let table = |keyspace: &str, name: &str| QualifiedName {
+        keyspace: Some(keyspace.into()),
+        name: name.into(),
+        span: Span::default(),
+    };
+    let names = statements
+        .iter()
+        .map(|statement| match statement {
+            Statement::Truncate(truncate) => vec![truncate.name.clone()],
+            Statement::Use(_) => vec![],
+            Statement::Select(select) => vec![select.from.clone()],
+            Statement::Batch(batch) => batch
+                .statements
+                .iter()
+                .map(|statement| match statement {
+                    BatchStatement::Insert(insert) => insert.table.clone(),
+                    BatchStatement::Delete(delete) => delete.table.clone(),
+                    BatchStatement::Update(update) => update.table.clone(),
+                })
+                .collect(),
+            Statement::GrantPermission(GrantPermission {
+                resource: Resource::Table(name),
+                ..
+            }) => vec![name.clone()],
+            Statement::Describe(Describe {
+                target:
+                    DescribeTarget::Table(name)
+                    | DescribeTarget::MaterializedView(name)
+                    | DescribeTarget::Index(name),
+                ..
+            }) => vec![name.clone()],
+            Statement::DropIndex(drop) => vec![drop.name.clone()],
+            statement => panic!("unexpected statement {:?}", statement),
+        })
+        .collect::<Vec<_>>();
+    assert_eq_ignoring_spans(
+        &names,
+        &vec![
+            vec![table("ks", "t1")],
+            vec![],
+            vec![table("Other", "t2")],
+            vec![table("Other", "t3"), table("ks", "t4")],
+            vec![table("Other", "t5")],
+            vec![table("Other", "t6")],
+            vec![table("Other", "v7")],
+            vec![table("Other", "i8")],
+            vec![table("Other", "i9")],
+            vec![table("ks", "i10")],
+        ],
+    );
+
+    let options = ParseOptions {
+        cqlsh: true,
+        ..ParseOptions::default()
+    };
+    let mut elements =
+        parse_script("COPY t1 TO STDOUT\nUSE other;\nCOPY t2 FROM STDIN", options).unwrap();
+    resolve_script_keyspaces(&mut elements, Some("ks"));
+    let names = elements
+        .iter()
+        .filter_map(|element| match element {
+            ScriptElement::ShellCommand(ShellCommand::Copy(copy)) => Some(copy.table.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq_ignoring_spans(&names, &vec![table("ks", "t1"), table("other", "t2")]);
+
+    let mut statements = parse("SELECT * FROM t");
+    resolve_keyspaces(&mut statements, None);
+    assert_eq!(
+        statements,
+        parse("SELECT * FROM t"),
+        "without a keyspace references are left unqualified"
+    );
+}
+
+#[test]
+fn test_describe() {
+    let describe = |input: &str| match parse(input).remove(0) {
+        Statement::Describe(describe) => describe,
+        statement => panic!("unexpected statement {:?}", statement),
+    };
+    let target = |input: &str| describe(input).target;
+    let name = |keyspace: Option<&str>, name: &str| QualifiedName {
+        keyspace: keyspace.map(Identifier::from),
+        name: name.into(),
+        span: Span::default(),
+    };
+
+    assert_eq!(target("DESCRIBE CLUSTER"), DescribeTarget::Cluster);
+    assert_eq!(
+        target("DESCRIBE FULL SCHEMA"),
+        DescribeTarget::Schema { full: true }
+    );
+    assert_eq!(
+        target("desc schema"),
+        DescribeTarget::Schema { full: false }
+    );
+    assert_eq!(target("DESCRIBE KEYSPACES"), DescribeTarget::Keyspaces);
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE ONLY KEYSPACE ks"),
+        &DescribeTarget::Keyspace {
+            only: true,
+            name: Some("ks".into()),
+        },
+    );
+    assert_eq!(
+        target("DESCRIBE KEYSPACE"),
+        DescribeTarget::Keyspace {
+            only: false,
+            name: None
+        }
+    );
+    assert_eq!(target("DESCRIBE TABLES"), DescribeTarget::Tables);
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE TABLE ks.t"),
+        &DescribeTarget::Table(name(Some("ks"), "t")),
+    );
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE INDEX ks.i"),
+        &DescribeTarget::Index(name(Some("ks"), "i")),
+    );
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE MATERIALIZED VIEW v"),
+        &DescribeTarget::MaterializedView(name(None, "v")),
+    );
+    assert_eq!(target("DESCRIBE TYPES"), DescribeTarget::Types);
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE TYPE ks.address"),
+        &DescribeTarget::Type(name(Some("ks"), "address")),
+    );
+    assert_eq!(target("DESCRIBE FUNCTIONS"), DescribeTarget::Functions);
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE FUNCTION ks.f"),
+        &DescribeTarget::Function(name(Some("ks"), "f")),
+    );
+    assert_eq!(target("DESCRIBE AGGREGATES"), DescribeTarget::Aggregates);
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE AGGREGATE ks.a"),
+        &DescribeTarget::Aggregate(name(Some("ks"), "a")),
+    );
+    assert_eq_ignoring_spans(
+        &target("DESCRIBE clusters"),
+        &DescribeTarget::Name(name(None, "clusters")),
+    );
+    assert_eq!(
+        describe("DESCRIBE KEYSPACE WITH INTERNALS"),
+        Describe {
+            target: DescribeTarget::Keyspace {
+                only: false,
+                name: None
+            },
+            with_internals: true,
+        }
+    );
+    assert_eq_ignoring_spans(
+        &describe("DESCRIBE TABLE ks.t WITH INTERNALS"),
+        &Describe {
+            target: DescribeTarget::Table(name(Some("ks"), "t")),
+            with_internals: true,
+        },
+    );
+}
+
+#[test]
+fn test_cqlsh_script() {
+    let cqlsh = ParseOptions {
+        cqlsh: true,
+        ..ParseOptions::default()
+    };
+    let script = "-- prepare the session
+        CONSISTENCY LOCAL_QUORUM
+        SERIAL CONSISTENCY;
+        TRACING ON
+        PAGING 100
+        EXPAND off;
+        /* load the data */
+        SOURCE 'schema.cql'
+        USE ks;
+        COPY ks.t (a, b) TO 'out.csv' WITH HEADER = true AND DELIMITER = '|'
+        COPY t FROM STDIN
+        CAPTURE OFF
+        // done
+        TRUNCATE t;";
+    assert_eq_ignoring_spans(
+        &parse_script(script, cqlsh).unwrap(),
+        &vec![
+            ScriptElement::ShellCommand(ShellCommand::Consistency(Some(
+                ConsistencyLevel::LocalQuorum,
+            ))),
+            ScriptElement::ShellCommand(ShellCommand::SerialConsistency(None)),
+            ScriptElement::ShellCommand(ShellCommand::Tracing(Some(true))),
+            ScriptElement::ShellCommand(ShellCommand::Paging(Some(Paging::PageSize(100)))),
+            ScriptElement::ShellCommand(ShellCommand::Expand(Some(false))),
+            ScriptElement::ShellCommand(ShellCommand::Source("schema.cql".to_string())),
+            ScriptElement::Statement(Statement::Use("ks".into())),
+            ScriptElement::ShellCommand(ShellCommand::Copy(Copy {
+                table: QualifiedName {
+                    keyspace: Some("ks".into()),
+                    name: "t".into(),
+                    span: Span::default(),
+                },
+                columns: vec!["a".into(), "b".into()],
+                direction: CopyDirection::To,
+                file: Some("out.csv".to_string()),
+                options: vec![
+                    Property {
+                        name: "header".into(),
+                        value: PropertyValue::Constant(Constant::Bool(true)),
+                    },
+                    Property {
+                        name: "delimiter".into(),
+                        value: PropertyValue::Constant(Constant::String("|".to_string())),
+                    },
+                ],
+            })),
+            ScriptElement::ShellCommand(ShellCommand::Copy(Copy {
+                table: QualifiedName {
+                    keyspace: None,
+                    name: "t".into(),
+                    span: Span::default(),
+                },
+                columns: vec![],
+                direction: CopyDirection::From,
+                file: None,
+                options: vec![],
+            })),
+            ScriptElement::ShellCommand(ShellCommand::Capture(Some(Capture::Off))),
+            ScriptElement::Statement(Statement::Truncate(Truncate {
+                name: QualifiedName {
+                    keyspace: None,
+                    name: "t".into(),
+                    span: Span::default(),
+                },
+            })),
+        ],
+    );
+
+    // Shell commands are not accepted outside of cqlsh mode
+    assert_eq!(
+        parse_script("TRACING ON", ParseOptions::default()),
+        Err(ParseError::Syntax { offset: 0 })
+    );
+    // Statements still need a terminator
+    assert_eq!(
+        parse_script("USE ks\nTRACING ON", cqlsh),
+        Err(ParseError::Syntax { offset: 7 })
+    );
+    assert_eq!(
+        parse_script("TRACING ON TRACING OFF", cqlsh),
+        Err(ParseError::Syntax { offset: 11 })
+    );
+
+    // Comments are part of CQL, so they are accepted anywhere whitespace is, in either mode
+    assert_eq_ignoring_spans(
+        &parse_with(
+            "-- first\nSELECT a, /* b */ b FROM // the table\n t WHERE a = '--' ; /* done */",
+            ParseOptions::default(),
+        )
+        .unwrap(),
+        &parse("SELECT a, b FROM t WHERE a = '--'"),
+    );
+    assert_eq_ignoring_spans(
+        &parse_with("SELECT a FROM/**/t", ParseOptions::default()).unwrap(),
+        &parse("SELECT a FROM t"),
+    );
+}
+
+#[test]
+fn test_literals() {
+    let where_ = |query: &str| match parse(query).remove(0) {
+        Statement::Select(select) => select.where_,
+        statement => panic!("expected a select, got {:?}", statement),
+    };
+    assert_eq_ignoring_spans(
+        &where_("SELECT * FROM t WHERE a = 123e4567-e89b-12d3-a456-426614174000 AND b = 0xcafe AND c = null AND d CONTAINS 1 AND e CONTAINS KEY 'k'"),
+        &vec![
+            RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("a".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::UUID(
+                    "123e4567-e89b-12d3-a456-426614174000".to_string()
+                ), Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("b".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Hex(vec![0xca, 0xfe]), Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::Comparison(RelationComparison {
+                lhs: Expr::Name("c".into()),
+                operator: ComparisonOperator::Equals,
+                rhs: Expr::Constant(Constant::Null, Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::Contains(RelationContains {
+                lhs: "d".into(),
+                rhs: Expr::Constant(Constant::Decimal(1), Span::default()),
+                span: Span::default(),
+            }),
+            RelationElement::ContainsKey(RelationContainsKey {
+                lhs: "e".into(),
+                rhs: Expr::Constant(Constant::String("k".to_string()), Span::default()),
+                span: Span::default(),
+            }),
+        ],
+    );
+}
+
+#[test]
+fn test_display() {
+    let render = |query: &str| parse(query)[0].to_string();
+    assert_eq!(
+        render("select a as \"B\", count(*) from ks.\"Table\" where \"select\" = 'it''s' limit 10"),
+        "SELECT a AS \"B\", count(*) FROM ks.\"Table\" WHERE \"select\" = 'it''s' LIMIT 10"
+    );
+    assert_eq!(
+        render("update t using ttl 10 set a = a + 1, l = [1] + l where k = 0x01"),
+        "UPDATE t USING TTL 10 SET a = a + 1, l = [1] + l WHERE k = 0x01"
+    );
+    assert_eq!(
+        render("create table t (a int, b text, c float, primary key ((a, b), c)) with clustering order by (c desc) and comment = 'x'"),
+        "CREATE TABLE t (a int, b text, c float, PRIMARY KEY ((a, b), c)) WITH CLUSTERING ORDER BY (c DESC) AND comment = 'x'"
+    );
+    assert_eq!(render("use \"Ks\""), "USE \"Ks\"");
+}
+
+#[test]
+fn test_display_round_trip() {
+    let queries = [
+        "SELECT * FROM t",
+        "SELECT JSON DISTINCT a, b AS \"B\", ks.\"Fn\"(c, ?) FROM ks.\"Table\" WHERE a = :name AND (b, c) > (1, 2) AND d IN (1.5, -2e10, -Infinity) AND e CONTAINS 'a' AND f CONTAINS KEY 'k' GROUP BY a ORDER BY b DESC PER PARTITION LIMIT 2 LIMIT ? ALLOW FILTERING",
+        "SELECT m['key'], token(a) FROM t WHERE a = 123e4567-e89b-12d3-a456-426614174000 AND b = 0xcafe AND c = null AND d = true AND e = 'it''s'",
+        "INSERT INTO \"T\" (\"from\", b) VALUES (1, {1, 2}) IF NOT EXISTS USING TTL 1 AND TIMESTAMP 2",
+        "UPDATE t USING TIMESTAMP 1 SET a = a + 1, b = b - {'x'}, c = [0] + c, d['k'] = 'v' WHERE k = 1 IF a = 2 AND b != 3",
+        "DELETE a, m['k'] FROM t USING TIMESTAMP 5 WHERE k = 1 IF EXISTS",
+        "BEGIN UNLOGGED BATCH USING TIMESTAMP 1 INSERT INTO t (a) VALUES (1); DELETE FROM t WHERE a = 2; APPLY BATCH",
+        "CREATE KEYSPACE IF NOT EXISTS \"Ks\" WITH replication = {'class': 'NetworkTopologyStrategy', 'dc1': 3, 'dc2': 1} AND durable_writes = false",
+        "CREATE KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'replication_factor': 3, 'dc2': 0}",
+        "ALTER KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 2}",
+        "DROP KEYSPACE IF EXISTS ks",
+        "CREATE TABLE IF NOT EXISTS ks.t (a int, b frozen<map<text, list<int>>>, c tuple<int, text>, d counter STATIC, e \"Udt\", PRIMARY KEY ((a, b), c)) WITH CLUSTERING ORDER BY (c DESC) AND COMPACT STORAGE AND compaction = {'class': 'LeveledCompactionStrategy'}",
+        "ALTER TABLE t ADD (a int, b text)",
+        "ALTER TABLE t DROP a",
+        "ALTER TABLE t RENAME a TO \"B\" AND c TO d",
+        "ALTER TABLE t WITH gc_grace_seconds = 0",
+        "DROP TABLE IF EXISTS t",
+        "TRUNCATE ks.t",
+        "CREATE CUSTOM INDEX IF NOT EXISTS idx ON t (keys(m)) USING 'org.Index' WITH OPTIONS = {'mode': 'CONTAINS'}",
+        "DROP INDEX ks.idx",
+        "CREATE MATERIALIZED VIEW v AS SELECT a, b FROM t WHERE a IS NOT NULL AND b IS NOT NULL PRIMARY KEY (b, a) WITH CLUSTERING ORDER BY (a ASC)",
+        "ALTER MATERIALIZED VIEW v WITH comment = 'c'",
+        "DROP MATERIALIZED VIEW IF EXISTS v",
+        "CREATE TYPE ks.address (street text, \"Zip\" int)",
+        "ALTER TYPE address RENAME street TO road",
+        "DROP TYPE IF EXISTS address",
+        "CREATE OR REPLACE FUNCTION f (a int) RETURNS NULL ON NULL INPUT RETURNS int LANGUAGE java AS $$ return a; $$",
+        "DROP FUNCTION f(int, text)",
+        "CREATE AGGREGATE a (int) SFUNC f STYPE int FINALFUNC g INITCOND 0",
+        "DROP AGGREGATE IF EXISTS a",
+        "CREATE TRIGGER trig ON t USING 'org.Trigger'",
+        "DROP TRIGGER trig ON t",
+        "CREATE ROLE \"Admin\" WITH PASSWORD = 'p''w' AND LOGIN = true AND OPTIONS = {'a': 1} AND ACCESS TO DATACENTERS {'dc1'}",
+        "ALTER ROLE r WITH SUPERUSER = false",
+        "DROP ROLE IF EXISTS r",
+        "CREATE USER u WITH PASSWORD 'p' NOSUPERUSER",
+        "DROP USER u",
+        "GRANT r TO u",
+        "REVOKE r FROM u",
+        "GRANT SELECT ON TABLE ks.t TO r",
+        "REVOKE ALL PERMISSIONS ON FUNCTION ks.f(int) FROM r",
+        "LIST ROLES OF r NORECURSIVE",
+        "LIST USERS",
+        "LIST MODIFY ON ALL KEYSPACES OF r",
+        "USE \"Ks\"",
+        "DESCRIBE KEYSPACE ks WITH INTERNALS",
+        "DESCRIBE \"tables\"",
+        "SELECT * FROM t WHERE a = Infinity AND b = -infinity AND c = 1.5e999 AND d = -1.5E999",
+        "UPDATE t SET s = {}, l = [], m = {1: {}} WHERE k = 1",
+        "INSERT INTO t (k, s) VALUES (1, {})",
+    ];
+    for query in queries {
+        let statement = parse(query).remove(0);
+        let rendered = statement.to_string();
+        assert_eq!(
+            strip_spans(&parse(&rendered)),
+            strip_spans(&vec![statement]),
+            "rendered as {}",
+            rendered
+        );
+    }
+
+    // NaN is not equal to itself, so compare the rendered forms instead
+    let statement = parse("SELECT * FROM t WHERE k = nan").remove(0);
+    let rendered = statement.to_string();
+    assert_eq!(rendered, "SELECT * FROM t WHERE k = NaN");
+    assert_eq!(parse(&rendered).remove(0).to_string(), rendered);
+}
+
+#[test]
+fn test_format_statement() {
+    let format = |query: &str, options: FormatOptions| format_statement(&parse(query)[0], options);
+    let narrow = FormatOptions {
+        max_width: 40,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format("select a, b from t where a = 1", FormatOptions::default()),
+        "SELECT a, b FROM t WHERE a = 1"
+    );
+    assert_eq!(
+        format("select a, \"B\", count(*) from ks.t where a = 1 and b in (1, 2) and c > 'long value' order by b desc limit 10", narrow),
+        "SELECT a, \"B\", count(*)
+FROM ks.t
+WHERE a = 1
+    AND b IN (1, 2)
+    AND c > 'long value'
+ORDER BY b DESC
+LIMIT 10"
+    );
+    assert_eq!(
+        format(
+            "select a, b from t where a = 1",
+            FormatOptions {
+                one_column_per_line: true,
+                keyword_case: KeywordCase::Lower,
+                indent: 2,
+                ..FormatOptions::default()
+            }
+        ),
+        "select
+  a,
+  b
+from t
+where a = 1"
+    );
+    assert_eq!(
+        format("update t using ttl 10 set value = 'a long string value', other = other + 1 where k = 0 if exists", narrow),
+        "UPDATE t
+USING TTL 10
+SET
+    value = 'a long string value',
+    other = other + 1
+WHERE k = 0
+IF EXISTS"
+    );
+    assert_eq!(
+        format("begin batch insert into t (a, b) values (1, 'some long text'); delete from t where a = 2 apply batch", narrow),
+        "BEGIN BATCH
+    INSERT INTO t (a, b)
+    VALUES (1, 'some long text');
+    DELETE FROM t WHERE a = 2;
+APPLY BATCH"
+    );
+
+    let table = "create table ks.t (a int, \"B\" text, c timeuuid, primary key (a, c)) with clustering order by (c desc) and comment = 'c' and gc_grace_seconds = 0";
+    assert_eq!(
+        format(table, narrow),
+        "CREATE TABLE ks.t (
+    a int,
+    \"B\" text,
+    c timeuuid,
+    PRIMARY KEY (a, c)
+) WITH CLUSTERING ORDER BY (c DESC)
+    AND comment = 'c'
+    AND gc_grace_seconds = 0"
+    );
+    assert_eq!(
+        format(
+            table,
+            FormatOptions {
+                align_with_options: true,
+                keyword_case: KeywordCase::Lower,
+                ..FormatOptions::default()
+            }
+        ),
+        "create table ks.t (
+    a int,
+    \"B\" text,
+    c timeuuid,
+    primary key (a, c)
+) with clustering order by (c desc)
+   and comment          = 'c'
+   and gc_grace_seconds = 0"
+    );
+
+    // Only keywords change case
+    assert_eq!(
+        format(
+            "select \"SELECT\", 'SELECT', 1e-5, ABCDEFAB-ABCD-ABCD-ABCD-ABCDEFABCDEF from t",
+            FormatOptions {
+                keyword_case: KeywordCase::Lower,
+                ..FormatOptions::default()
+            }
+        ),
+        "select \"SELECT\", 'SELECT', 1e-5, ABCDEFAB-ABCD-ABCD-ABCD-ABCDEFABCDEF from t"
+    );
+}
+
+#[test]
+fn test_format_script() {
+    let cqlsh = ParseOptions {
+        cqlsh: true,
+        ..ParseOptions::default()
+    };
+    let script = "-- schema for ks
+use ks;   create table t (a int primary key) ; -- the table
+
+
+/* seed
+   data */
+insert into t (a) values (1)
+;
+consistency quorum
+";
+    assert_eq!(
+        format_script(script, cqlsh, FormatOptions::default()),
+        Ok("-- schema for ks
+USE ks;
+CREATE TABLE t (a int, PRIMARY KEY (a)); -- the table
+
+/* seed
+   data */
+INSERT INTO t (a) VALUES (1);
+CONSISTENCY QUORUM;
+"
+        .to_string())
+    );
+    assert_eq!(
+        format_script(
+            script,
+            cqlsh,
+            FormatOptions {
+                preserve_comments: false,
+                ..FormatOptions::default()
+            }
+        ),
+        Ok("USE ks;
+CREATE TABLE t (a int, PRIMARY KEY (a));
+
+INSERT INTO t (a) VALUES (1);
+CONSISTENCY QUORUM;
+"
+        .to_string())
+    );
+    assert_eq!(
+        format_script(
+            "select * from",
+            ParseOptions::default(),
+            FormatOptions::default()
+        ),
+        Err(ParseError::Syntax { offset: 13 })
+    );
+
+    let script = "select a, -- the key
+    b /* b */ from t // the table
+    -- only rows with c
+    where c = '-- not a comment' and d = 1;
+insert into t (a) /* the values */ values (1);";
+    let options = FormatOptions {
+        keyword_case: KeywordCase::Lower,
+        one_column_per_line: true,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_script(script, ParseOptions::default(), options),
+        Ok("select
+    a,
+    -- the key
+    b
+/* b */
+from t
+// the table
+-- only rows with c
+where c = '-- not a comment' and d = 1;
+/* the values */
+insert into t (a) values (1);
+"
+        .to_string())
+    );
+    assert_eq!(
+        format_script(
+            script,
+            ParseOptions::default(),
+            FormatOptions {
+                preserve_comments: false,
+                ..options
+            }
+        ),
+        Ok("select
+    a,
+    b
+from t
+where c = '-- not a comment' and d = 1;
+insert into t (a) values (1);
+"
+        .to_string())
+    );
+}
+
+#[test]
+fn test_spans() {
+    let source = "USE ks;\nSELECT a, count(b) AS c\n  FROM ks.t WHERE a = 1 AND m['k'] > 2\n  ORDER BY b DESC;";
+    let text = |span: Span| &source[span.start..span.end];
+    let statements = parse_with_spans(source, ParseOptions::default()).unwrap();
+    assert_eq!(text(statements[0].1), "USE ks");
+    assert_eq!(
+        text(statements[1].1),
+        "SELECT a, count(b) AS c\n  FROM ks.t WHERE a = 1 AND m['k'] > 2\n  ORDER BY b DESC"
+    );
+
+    let select = match &statements[1].0 {
+        Statement::Select(select) => select,
+        statement => panic!("expected a select, got {:?}", statement),
+    };
+    assert_eq!(text(select.select[0].span), "a");
+    assert_eq!(text(select.select[1].span), "count(b) AS c");
+    assert_eq!(text(select.select[1].expr.span()), "count(b)");
+    assert_eq!(text(select.select[1].as_alias.as_ref().unwrap().span), "c");
+    assert_eq!(text(select.from.span), "ks.t");
+    assert_eq!(text(select.from.keyspace.as_ref().unwrap().span), "ks");
+    assert_eq!(text(select.from.name.span), "t");
+    let relations: Vec<_> = select.where_.iter().map(|r| text(r.span())).collect();
+    assert_eq!(relations, ["a = 1", "m['k'] > 2"]);
+    match &select.where_[0] {
+        RelationElement::Comparison(comparison) => {
+            assert_eq!(text(comparison.lhs.span()), "a");
+            assert_eq!(text(comparison.rhs.span()), "1");
+        }
+        relation => panic!("expected a comparison, got {:?}", relation),
+    }
+    assert_eq!(text(select.order_by[0].span), "b DESC");
+    assert_eq!(text(select.order_by[0].name.span), "b");
+
+    let span = select.where_[1].span();
+    assert_eq!(
+        span.start_location(source),
+        Location {
+            line: 3,
+            column: 29
+        }
+    );
+    assert_eq!(
+        span.end_location(source),
+        Location {
+            line: 3,
+            column: 39
+        }
+    );
+    assert_eq!(
+        statements[1].1.start_location(source),
+        Location { line: 2, column: 1 }
+    );
+    assert_eq!(line_col(source, 0), Location { line: 1, column: 1 });
+    assert_eq!(line_col("é\nàb", 5), Location { line: 2, column: 2 });
+
+    // Every expression and identifier has a span, including within collections
+    let source = "INSERT INTO t (k, s) VALUES (?, {'x', :y}) IF NOT EXISTS";
+    let text = |span: Span| &source[span.start..span.end];
+    let insert = match parse_with_spans(source, ParseOptions::default())
+        .unwrap()
+        .remove(0)
+        .0
+    {
+        Statement::Insert(insert) => insert,
+        statement => panic!("expected an insert, got {:?}", statement),
+    };
+    let columns: Vec<_> = insert.columns.iter().map(|c| text(c.span)).collect();
+    assert_eq!(columns, ["k", "s"]);
+    let values: Vec<_> = insert.values.iter().map(|v| text(v.span())).collect();
+    assert_eq!(values, ["?", "{'x', :y}"]);
+    match &insert.values[1] {
+        Expr::Set(values, _) => {
+            let values: Vec<_> = values.iter().map(|v| text(v.span())).collect();
+            assert_eq!(values, ["'x'", ":y"]);
+        }
+        expr => panic!("expected a set, got {:?}", expr),
+    }
+
+    let source =
+        "CREATE MATERIALIZED VIEW v AS SELECT * FROM t WHERE k IS NOT NULL PRIMARY KEY (k)";
+    let text = |span: Span| &source[span.start..span.end];
+    let view = match parse_with_spans(source, ParseOptions::default())
+        .unwrap()
+        .remove(0)
+        .0
+    {
+        Statement::CreateMaterializedView(view) => view,
+        statement => panic!("expected a view, got {:?}", statement),
+    };
+    assert_eq!(text(view.select.select[0].expr.span()), "*");
+    assert_eq!(text(view.select.where_[0].span()), "k IS NOT NULL");
+    assert_eq!(text(view.partition_key[0].span), "k");
+
+    // Spans take part in comparisons
+    assert_ne!(parse("SELECT a FROM t"), parse("SELECT  a FROM t"));
+    assert_eq_ignoring_spans(&parse("SELECT a FROM t"), &parse("SELECT  a FROM t"));
+
+    // Spans of nested statements and clauses are measured from the start of the input
+    let source = "BEGIN BATCH UPDATE t USING TTL 5 SET a = a + 1 WHERE k = 0; APPLY BATCH";
+    let text = |span: Span| &source[span.start..span.end];
+    let statement = parse_with_spans(source, ParseOptions::default())
+        .unwrap()
+        .remove(0)
+        .0;
+    let update = match statement {
+        Statement::Batch(Batch { mut statements, .. }) => match statements.remove(0) {
+            BatchStatement::Update(update) => update,
+            statement => panic!("expected an update, got {:?}", statement),
+        },
+        statement => panic!("expected a batch, got {:?}", statement),
+    };
+    assert_eq!(text(update.table.span), "t");
+    assert_eq!(text(update.using.unwrap().span), "USING TTL 5");
+    assert_eq!(text(update.set[0].span), "a = a + 1");
+
+    // Role names given as strings span their quotes
+    let source = "GRANT admin TO 'alice'; ALTER TABLE t WITH comment = 'c'";
+    let text = |span: Span| &source[span.start..span.end];
+    let statements = parse_with_spans(source, ParseOptions::default()).unwrap();
+    match &statements[0].0 {
+        Statement::GrantRole(grant) => {
+            assert_eq!(text(grant.role.span), "admin");
+            assert_eq!(text(grant.grantee.span), "'alice'");
+            assert_eq!(grant.grantee, "alice");
+        }
+        statement => panic!("expected a grant, got {:?}", statement),
+    }
+    match &statements[1].0 {
+        Statement::AlterTable(AlterTable {
+            operation: AlterTableOperation::With(options),
+            ..
+        }) => assert_eq!(text(options[0].name.span), "comment"),
+        statement => panic!("expected an alter table, got {:?}", statement),
+    }
+}
+
+#[derive(Default)]
+struct References<'ast> {
+    columns: Vec<&'ast str>,
+    identifiers: Vec<&'ast str>,
+    constants: Vec<&'ast Constant>,
+    functions: Vec<&'ast str>,
+    types: Vec<&'ast CqlType>,
+}
+
+impl<'ast> Visitor<'ast> for References<'ast> {
+    fn visit_column(&mut self, column: &'ast Identifier) {
+        self.columns.push(column);
+        self.visit_identifier(column);
+    }
+
+    fn visit_function_call(&mut self, call: &'ast FunctionCall) {
+        self.functions.push(&call.function.name);
+        walk_function_call(self, call);
+    }
+
+    fn visit_constant(&mut self, constant: &'ast Constant) {
+        self.constants.push(constant);
+    }
+
+    fn visit_identifier(&mut self, identifier: &'ast Identifier) {
+        self.identifiers.push(identifier);
+    }
+
+    fn visit_type(&mut self, cql_type: &'ast CqlType) {
+        self.types.push(cql_type);
+        walk_type(self, cql_type);
+    }
+}
+
+fn references(statement: &Statement) -> References<'_> {
+    let mut references = References::default();
+    references.visit_statement(statement);
+    references
+}
+
+#[test]
+fn test_visitor() {
+    let statements = parse(
+        "SELECT a, writetime(b) AS wb, m['k'] FROM ks.t WHERE c IN (1, 2) AND s CONTAINS 'x' ORDER BY d;
+         UPDATE t SET a = a + 1, l = [?] WHERE k = token(j) IF n = null;
+         BEGIN BATCH INSERT INTO t (a, b) VALUES (1, 'one'); DELETE m['k'] FROM t WHERE k = 0; APPLY BATCH;
+         CREATE TABLE t (k int PRIMARY KEY, m map<text, frozen<address>>) WITH comment = 'c';
+         CREATE MATERIALIZED VIEW v AS SELECT k, a FROM t WHERE a IS NOT NULL PRIMARY KEY (a, k)",
+    );
+
+    let select = references(&statements[0]);
+    assert_eq!(select.columns, ["a", "b", "m", "c", "s", "d"]);
+    assert_eq!(
+        select.identifiers,
+        ["a", "writetime", "b", "wb", "m", "ks", "t", "c", "s", "d"]
+    );
+    assert_eq!(select.functions, ["writetime"]);
+    assert_eq!(
+        select.constants,
+        [
+            &Constant::String("k".to_string()),
+            &Constant::Decimal(1),
+            &Constant::Decimal(2),
+            &Constant::String("x".to_string()),
+        ]
+    );
+
+    let update = references(&statements[1]);
+    assert_eq!(update.columns, ["a", "l", "k", "j", "n"]);
+    assert_eq!(update.functions, ["token"]);
+    assert_eq!(update.constants, [&Constant::Decimal(1), &Constant::Null]);
+
+    let batch = references(&statements[2]);
+    assert_eq!(batch.columns, ["a", "b", "m", "k"]);
+    assert_eq!(batch.identifiers, ["t", "a", "b", "m", "t", "k"]);
+    assert_eq!(batch.constants.len(), 4);
+
+    let create_table = references(&statements[3]);
+    assert_eq!(create_table.columns, ["k", "m", "k"]);
+    assert_eq!(
+        create_table.identifiers,
+        ["t", "k", "m", "address", "k", "comment"]
+    );
+    assert_eq_ignoring_spans(
+        &create_table.types,
+        &[
+            &CqlType::Native(NativeType::Int),
+            &CqlType::Map(
+                Box::new(CqlType::Native(NativeType::Text)),
+                Box::new(CqlType::Frozen(Box::new(CqlType::UserDefined(
+                    QualifiedName {
+                        keyspace: None,
+                        name: "address".into(),
+                        span: Span::default(),
+                    },
+                )))),
+            ),
+            &CqlType::Native(NativeType::Text),
+            &CqlType::Frozen(Box::new(CqlType::UserDefined(QualifiedName {
+                keyspace: None,
+                name: "address".into(),
+                span: Span::default(),
+            }))),
+            &CqlType::UserDefined(QualifiedName {
+                keyspace: None,
+                name: "address".into(),
+                span: Span::default(),
+            }),
+        ],
+    );
+    assert_eq!(create_table.constants, [&Constant::String("c".to_string())]);
+
+    let view = references(&statements[4]);
+    assert_eq!(view.columns, ["k", "a", "a", "a", "k"]);
+    assert_eq!(view.identifiers, ["v", "k", "a", "t", "a", "a", "k"]);
+
+    let roles = parse(
+        "GRANT SELECT ON ROLE bob TO 'alice'; LIST ALL OF carol; GRANT admin TO dave; DROP USER erin",
+    );
+    let identifiers: Vec<_> = roles
+        .iter()
+        .flat_map(|statement| references(statement).identifiers)
+        .collect();
+    assert_eq!(
+        identifiers,
+        ["bob", "alice", "carol", "admin", "dave", "erin"]
+    );
+}
+
+/// Records every hook in the order it is called
+#[derive(Default)]
+struct Trace(Vec<String>);
+
+impl<'ast> Visitor<'ast> for Trace {
+    fn visit_statement(&mut self, statement: &'ast Statement) {
+        self.0.push("statement".to_string());
+        walk_statement(self, statement);
+    }
+
+    fn visit_select_element(&mut self, element: &'ast SelectElement) {
+        self.0.push("select element".to_string());
+        walk_select_element(self, element);
+    }
+
+    fn visit_relation(&mut self, relation: &'ast RelationElement) {
+        self.0.push("relation".to_string());
+        walk_relation(self, relation);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        self.0.push("expr".to_string());
+        walk_expr(self, expr);
+    }
+
+    fn visit_function_call(&mut self, call: &'ast FunctionCall) {
+        self.0.push("function call".to_string());
+        walk_function_call(self, call);
+    }
+
+    fn visit_qualified_name(&mut self, name: &'ast QualifiedName) {
+        self.0.push("qualified name".to_string());
+        walk_qualified_name(self, name);
+    }
+
+    fn visit_constant(&mut self, constant: &'ast Constant) {
+        self.0.push(format!("constant {}", constant));
+    }
+
+    fn visit_identifier(&mut self, identifier: &'ast Identifier) {
+        self.0.push(format!("identifier {}", identifier));
+    }
+
+    fn visit_type(&mut self, cql_type: &'ast CqlType) {
+        self.0.push(format!("type {}", cql_type));
+        walk_type(self, cql_type);
+    }
+}
+
+impl VisitorMut for Trace {
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        self.0.push("statement".to_string());
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_select_element_mut(&mut self, element: &mut SelectElement) {
+        self.0.push("select element".to_string());
+        walk_select_element_mut(self, element);
+    }
+
+    fn visit_relation_mut(&mut self, relation: &mut RelationElement) {
+        self.0.push("relation".to_string());
+        walk_relation_mut(self, relation);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        self.0.push("expr".to_string());
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_function_call_mut(&mut self, call: &mut FunctionCall) {
+        self.0.push("function call".to_string());
+        walk_function_call_mut(self, call);
+    }
+
+    fn visit_qualified_name_mut(&mut self, name: &mut QualifiedName) {
+        self.0.push("qualified name".to_string());
+        walk_qualified_name_mut(self, name);
+    }
+
+    fn visit_constant_mut(&mut self, constant: &mut Constant) {
+        self.0.push(format!("constant {}", constant));
+    }
+
+    fn visit_identifier_mut(&mut self, identifier: &mut Identifier) {
+        self.0.push(format!("identifier {}", identifier));
+    }
+
+    fn visit_type_mut(&mut self, cql_type: &mut CqlType) {
+        self.0.push(format!("type {}", cql_type));
+        walk_type_mut(self, cql_type);
+    }
+}
+
+#[test]
+fn test_visitor_order() {
+    let query = "SELECT a, ks.f(b, 1) AS x FROM ks.t WHERE m['k'] = 2 AND c IN (3, 4) ORDER BY c;
+                 UPDATE t SET l = l + [5] WHERE k = 6 IF EXISTS;
+                 CREATE TYPE ks.address (street text, phones map<text, frozen<phone>>)";
+    let expected = [
+        "statement",
+        "select element",
+        "expr",
+        "identifier a",
+        "select element",
+        "expr",
+        "function call",
+        "qualified name",
+        "identifier ks",
+        "identifier f",
+        "expr",
+        "identifier b",
+        "expr",
+        "constant 1",
+        "identifier x",
+        "qualified name",
+        "identifier ks",
+        "identifier t",
+        "relation",
+        "expr",
+        "identifier m",
+        "expr",
+        "constant 'k'",
+        "expr",
+        "constant 2",
+        "relation",
+        "expr",
+        "identifier c",
+        "expr",
+        "constant 3",
+        "expr",
+        "constant 4",
+        "identifier c",
+        "statement",
+        "qualified name",
+        "identifier t",
+        "expr",
+        "identifier l",
+        "expr",
+        "expr",
+        "constant 5",
+        "relation",
+        "expr",
+        "identifier k",
+        "expr",
+        "constant 6",
+        "statement",
+        "qualified name",
+        "identifier ks",
+        "identifier address",
+        "identifier street",
+        "type text",
+        "identifier phones",
+        "type map<text, frozen<phone>>",
+        "type text",
+        "type frozen<phone>",
+        "type phone",
+        "qualified name",
+        "identifier phone",
+    ];
+
+    // Both visitors call their hooks in the same order: parents before children, children in
+    // the order they are written
+    let mut statements = parse(query);
+    let mut trace = Trace::default();
+    for statement in &statements {
+        trace.visit_statement(statement);
+    }
+    assert_eq!(trace.0, expected);
+
+    let mut trace = Trace::default();
+    for statement in &mut statements {
+        trace.visit_statement_mut(statement);
+    }
+    assert_eq!(trace.0, expected);
+}
+
+struct Rewrite {
+    keyspace: &'static str,
+    max_rows: u64,
+}
+
+impl VisitorMut for Rewrite {
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        if let Statement::Select(select) = statement {
+            if select.limit.is_none() {
+                select.limit = Some(Limit::Literal(self.max_rows));
+            }
+        }
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_qualified_name_mut(&mut self, name: &mut QualifiedName) {
+        if name.keyspace.as_deref() == Some("staging") {
+            name.keyspace = Some(self.keyspace.into());
+        }
+    }
+
+    fn visit_select_element_mut(&mut self, element: &mut SelectElement) {
+        // Replaced before walking, so the new call and the name inside it are visited
+        if let Expr::Name(name) = &element.expr {
+            if name == "secret" {
+                element.expr = Expr::FunctionCall(FunctionCall {
+                    function: QualifiedName {
+                        keyspace: Some("staging".into()),
+                        name: "mask".into(),
+                        span: Span::default(),
+                    },
+                    args: vec![element.expr.clone()],
+                    span: Span::default(),
+                });
+            }
+        }
+        walk_select_element_mut(self, element);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Constant(_, span) = expr {
+            *expr = Expr::BindMarker(BindMarker::Anonymous, *span);
+        }
+        walk_expr_mut(self, expr);
+    }
+
+    // Only columns are renamed, not a table or function that shares the name
+    fn visit_column_mut(&mut self, column: &mut Identifier) {
+        if *column == "secret" {
+            column.value.make_ascii_uppercase();
+        }
+    }
+}
+
+#[test]
+fn test_visitor_mut() {
+    let mut statements = parse(
+        "SELECT a, secret FROM staging.secret WHERE k = 1 AND c IN (2, 3) AND secret CONTAINS 'x';
+         SELECT a FROM t LIMIT 10;
+         INSERT INTO staging.t (k, secret) VALUES (1, {'a': [0x01]}) USING TTL 60;
+         BEGIN BATCH UPDATE staging.t SET secret = 'b' WHERE k = 2; APPLY BATCH",
+    );
+    let mut rewrite = Rewrite {
+        keyspace: "production",
+        max_rows: 1000,
+    };
+    for statement in &mut statements {
+        rewrite.visit_statement_mut(statement);
+    }
+    let rendered: Vec<String> = statements.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        rendered,
+        [
+            "SELECT a, production.mask(\"SECRET\") FROM production.secret WHERE k = ? AND c IN (?, ?) AND \"SECRET\" CONTAINS ? LIMIT 1000",
+            "SELECT a FROM t LIMIT 10",
+            "INSERT INTO production.t (k, \"SECRET\") VALUES (?, {?: [?]}) USING TTL 60",
+            "BEGIN BATCH UPDATE production.t SET \"SECRET\" = ? WHERE k = ?; APPLY BATCH",
+        ]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    // The example in the `ast` module documentation
+    let statement = parse("SELECT * FROM t WHERE k = ?").remove(0);
+    assert_eq!(
+        serde_json::to_value(&statement).unwrap(),
+        serde_json::json!({"type": "Select", "value": {
+            "distinct": false, "json": false,
+            "select": [{"expr": {"type": "Wildcard", "value": {"start": 7, "end": 8}}, "as_alias": null,
+                "span": {"start": 7, "end": 8}}],
+            "from": {"keyspace": null, "name": {"value": "t", "span": {"start": 14, "end": 15}},
+                "span": {"start": 14, "end": 15}},
+            "where_": [{"type": "Comparison", "value": {
+                "lhs": {"type": "Name", "value": {"value": "k", "span": {"start": 22, "end": 23}}},
+                "operator": {"type": "Equals"},
+                "rhs": {"type": "BindMarker", "value": [{"type": "Anonymous"}, {"start": 26, "end": 27}]},
+                "span": {"start": 22, "end": 27}}}],
+            "group_by": [], "order_by": [], "per_partition_limit": null, "limit": null,
+            "allow_filtering": false, "using": null}})
+    );
+
+    let statements = parse(
+        "SELECT DISTINCT ks.f(a, [1, 2], {'k': 0x01}) AS x FROM ks.t WHERE m['k'] IN (1.5, -2) AND s CONTAINS KEY 'x' ORDER BY c DESC LIMIT :n;
+         INSERT INTO t (k, v) VALUES (123e4567-e89b-12d3-a456-426614174000, (true, null)) IF NOT EXISTS USING TTL 5;
+         BEGIN UNLOGGED BATCH UPDATE t SET l = [1] + l WHERE k = 1 IF v != 2; DELETE FROM t WHERE k = 1; APPLY BATCH;
+         CREATE TABLE IF NOT EXISTS ks.t (k int, c text, m map<text, frozen<tuple<int, address>>>, PRIMARY KEY ((k), c)) WITH CLUSTERING ORDER BY (c DESC) AND compaction = {'class': 'LeveledCompactionStrategy'};
+         CREATE KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'dc1': 3} AND durable_writes = false;
+         CREATE FUNCTION f (a int) CALLED ON NULL INPUT RETURNS int LANGUAGE java AS $$ return a; $$;
+         CREATE ROLE r WITH PASSWORD = 'p' AND LOGIN = true;
+         GRANT SELECT ON ALL KEYSPACES TO r;
+         DESCRIBE TABLE ks.t",
+    );
+    for statement in statements {
+        let json = serde_json::to_string(&statement).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Statement>(&json).unwrap(),
+            statement,
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn test_fingerprint() {
+    let shape = |query: &str| fingerprint(&parse(query).remove(0)).0;
+
+    let (select, hash) = fingerprint(&parse("SELECT * FROM t WHERE id = 1").remove(0));
+    assert_eq!(select, "select * from t where id = ?");
+    // Pinned, as the hash must not change between versions
+    assert_eq!(hash, 0x9035_6c2a_5f55_a6f1);
+    assert_eq!(
+        fingerprint(&parse("select *   from T where ID = 2").remove(0)),
+        (select, hash)
+    );
+
+    assert_eq!(
+        shape(
+            "SELECT a FROM ks.t WHERE k IN (1, 2, 3) AND c IN (?, ?) AND s CONTAINS 'x' LIMIT 10"
+        ),
+        "select a from ks.t where k in (?) and c in (?) and s contains ? limit ?"
+    );
+    assert_eq!(
+        shape("SELECT a FROM ks.t WHERE k IN (4) AND c IN (5, 6) AND s CONTAINS 'y' LIMIT 20"),
+        shape(
+            "SELECT a FROM ks.t WHERE k IN (1, 2, 3) AND c IN (?, ?) AND s CONTAINS 'x' LIMIT 10"
+        )
+    );
+    assert_eq!(
+        shape("SELECT \"Name\", 'text' FROM t WHERE m['K'] = 0xcafe AND b = :Marker AND d = token(-1.5)"),
+        "select \"Name\", ? from t where m[?] = ? and b = :marker and d = token(?)"
+    );
+    assert_eq!(
+        shape("INSERT INTO t (k, l, m) VALUES (1, [1, 2], {'a': now()}) IF NOT EXISTS USING TTL 60 AND TIMESTAMP ?"),
+        "insert into t (k, l, m) values (?, ?, {?: now()}) if not exists using ttl ? and timestamp ?"
+    );
+    assert_eq!(
+        shape("BEGIN BATCH USING TIMESTAMP 1 UPDATE t USING TTL 5 SET a = a + 1 WHERE k = 2 IF b = null; APPLY BATCH"),
+        "begin batch using timestamp ? update t using ttl ? set a = a + ? where k = ? if b = ?; apply batch"
+    );
+    assert_eq!(
+        shape("ALTER TABLE t WITH comment = 'It''s' AND compaction = {'class': 'LeveledCompactionStrategy'}"),
+        "alter table t with comment = ? and compaction = {?: ?}"
+    );
+    assert_eq!(
+        shape("SELECT a FROM t WHERE k IN ? AND c IN :Cs AND s CONTAINS ? AND m CONTAINS KEY 'k'"),
+        "select a from t where k in ? and c in :cs and s contains ? and m contains key ?"
+    );
+    assert_eq!(
+        shape("CREATE INDEX i ON t (v) USING 'SAI' WITH OPTIONS = {'case_sensitive': 'false'}"),
+        "create index i on t (v) using 'SAI' with options = {?: ?}"
+    );
+    assert_eq!(
+        shape("CREATE FUNCTION f (a int) CALLED ON NULL INPUT RETURNS int LANGUAGE java AS $$ return A; $$"),
+        "create function f (a int) called on null input returns int language java as ?"
+    );
+    assert_eq!(
+        shape("CREATE ROLE r WITH PASSWORD = 'secret' AND LOGIN = true"),
+        "create role r with password = '*****' and login = true"
+    );
+    // Constants are only written as `?` while the shape is rendered
+    let statement = parse("ALTER TABLE t WITH comment = 'c'").remove(0);
+    fingerprint(&statement);
+    assert_eq!(statement.to_string(), "ALTER TABLE t WITH comment = 'c'");
+    assert_ne!(
+        fingerprint(&parse("SELECT a FROM t WHERE k = 1").remove(0)).1,
+        fingerprint(&parse("SELECT b FROM t WHERE k = 1").remove(0)).1
+    );
+}